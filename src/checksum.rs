@@ -0,0 +1,181 @@
+/// Builds the lookup table for a reflected CRC-32 with the given polynomial.
+const fn crc32_table(poly: u32) -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ poly
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+const CRC32_TABLE: [u32; 256] = crc32_table(0xEDB8_8320);
+const CRC32C_TABLE: [u32; 256] = crc32_table(0x82F6_3B78);
+
+// Continues a CRC over `data` from the uninverted register `crc`.
+fn crc32_update(table: &[u32; 256], crc: u32, data: &[u8]) -> u32 {
+    data.iter().fold(crc, |crc, &b| {
+        table[((crc ^ b as u32) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+/// CRC-32 (IEEE 802.3), as used by zip, gzip and PNG.
+pub fn crc32(data: &[u8]) -> u32 {
+    !crc32_update(&CRC32_TABLE, !0, data)
+}
+
+/// CRC-32C (Castagnoli), as used by iSCSI, ext4 and SSE4.2's `crc32`.
+pub fn crc32c(data: &[u8]) -> u32 {
+    !crc32_update(&CRC32C_TABLE, !0, data)
+}
+
+// Continues Adler-32's two sums over `data`; both stay reduced.
+fn adler32_update((mut a, mut b): (u32, u32), data: &[u8]) -> (u32, u32) {
+    const MOD: u32 = 65521;
+    // Largest block that cannot overflow the u32 accumulators before reducing
+    const BLOCK: usize = 5552;
+    for chunk in data.chunks(BLOCK) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+    (a, b)
+}
+
+/// Adler-32, as used by zlib.
+pub fn adler32(data: &[u8]) -> u32 {
+    let (a, b) = adler32_update((1, 0), data);
+    (b << 16) | a
+}
+
+/// Wrapping sum of all bytes.
+pub fn sum32(data: &[u8]) -> u32 {
+    data.iter().fold(0u32, |acc, &b| acc.wrapping_add(b as u32))
+}
+
+/// XOR of all bytes.
+pub fn xor8(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |acc, &b| acc ^ b)
+}
+
+/// Every checksum above at once, over data fed in pieces with `update`, so
+/// a long range can be summed a chunk at a time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checksums {
+    crc32: u32,
+    crc32c: u32,
+    adler32: (u32, u32),
+    sum32: u32,
+    xor8: u8,
+}
+
+impl Default for Checksums {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Checksums {
+    pub fn new() -> Self {
+        Self {
+            crc32: !0,
+            crc32c: !0,
+            adler32: (1, 0),
+            sum32: 0,
+            xor8: 0,
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.crc32 = crc32_update(&CRC32_TABLE, self.crc32, data);
+        self.crc32c = crc32_update(&CRC32C_TABLE, self.crc32c, data);
+        self.adler32 = adler32_update(self.adler32, data);
+        self.sum32 = self.sum32.wrapping_add(sum32(data));
+        self.xor8 ^= xor8(data);
+    }
+
+    pub fn crc32(&self) -> u32 {
+        !self.crc32
+    }
+
+    pub fn crc32c(&self) -> u32 {
+        !self.crc32c
+    }
+
+    pub fn adler32(&self) -> u32 {
+        let (a, b) = self.adler32;
+        (b << 16) | a
+    }
+
+    pub fn sum32(&self) -> u32 {
+        self.sum32
+    }
+
+    pub fn xor8(&self) -> u8 {
+        self.xor8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_values() {
+        // The standard "123456789" check input
+        let data = b"123456789";
+        assert_eq!(crc32(data), 0xCBF4_3926);
+        assert_eq!(crc32c(data), 0xE306_9283);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+        assert_eq!(sum32(data), 477);
+        assert_eq!(xor8(data), 0x31);
+    }
+
+    #[test]
+    fn test_empty_input() {
+        assert_eq!(crc32(&[]), 0);
+        assert_eq!(crc32c(&[]), 0);
+        assert_eq!(adler32(&[]), 1);
+        assert_eq!(sum32(&[]), 0);
+        assert_eq!(xor8(&[]), 0);
+    }
+
+    #[test]
+    fn test_adler32_long_input() {
+        // Crosses several reduction blocks with maximal bytes
+        let data = vec![0xFFu8; 100_000];
+        let (mut a, mut b) = (1u64, 0u64);
+        for &byte in &data {
+            a = (a + byte as u64) % 65521;
+            b = (b + a) % 65521;
+        }
+        assert_eq!(adler32(&data), ((b << 16) | a) as u32);
+    }
+
+    #[test]
+    fn test_checksums_in_pieces() {
+        let data: Vec<u8> = (0..20_000u32).map(|i| (i * 7 + i / 300) as u8).collect();
+        let mut sums = Checksums::new();
+        for piece in data.chunks(3_001) {
+            sums.update(piece);
+        }
+        assert_eq!(sums.crc32(), crc32(&data));
+        assert_eq!(sums.crc32c(), crc32c(&data));
+        assert_eq!(sums.adler32(), adler32(&data));
+        assert_eq!(sums.sum32(), sum32(&data));
+        assert_eq!(sums.xor8(), xor8(&data));
+    }
+}
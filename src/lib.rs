@@ -1,2 +1,7 @@
+pub mod checksum;
 pub mod search;
-pub mod ui;
\ No newline at end of file
+pub mod strings;
+pub mod ui;
+
+#[cfg(test)]
+mod test_util;
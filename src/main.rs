@@ -1,6 +1,13 @@
 use rsearch::ui::BinarySearchApp;
 use eframe::egui;
+use std::path::PathBuf;
 fn main() -> eframe::Result<()> {
+    // Diagnostics are silent unless enabled, e.g. `RUST_LOG=rsearch=trace`
+    env_logger::init();
+
+    // `rsearch <file>` opens the file on startup
+    let initial_path = std::env::args_os().nth(1).map(PathBuf::from);
+
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([BinarySearchApp::APP_MIN_WIDTH, 800.0]),
@@ -8,8 +15,8 @@ fn main() -> eframe::Result<()> {
     };
     
     eframe::run_native(
-        "Binary Search Tool",
+        BinarySearchApp::APP_NAME,
         options,
-        Box::new(|cc| Ok(Box::new(BinarySearchApp::new(cc)))),
+        Box::new(|cc| Ok(Box::new(BinarySearchApp::new(cc, initial_path)))),
     )
 }
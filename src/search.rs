@@ -1,12 +1,24 @@
 use std::{
-    sync::{Arc, mpsc},
+    ops::Range,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc,
+    },
     thread::{self, JoinHandle},
 };
 
 use color_eyre::{Result as EyreReult, eyre::eyre};
 use memchr::memmem;
 use memmap2::Mmap;
+use serde::{Deserialize, Serialize};
 
+/// Bytes a search thread reads, while the UI thread may be reading them too.
+///
+/// A haystack must not change once it is handed to a search, since the
+/// thread reads it without locking. Shared buffers such as `Arc<Mmap>` are
+/// only ever read; edits go to a copy of the bytes instead (see
+/// `FilePanel::write_byte`), so a running search keeps the bytes it started on.
 pub trait Haystack: Send + 'static {
     fn as_bytes(&self) -> &[u8];
 }
@@ -83,12 +95,120 @@ impl Haystack for Arc<Mmap> {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Where each part of a `Concat` lies in it, to map an offset in the whole
+/// back to the part it came from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PartMap {
+    // Offset just past each part, in order
+    ends: Vec<usize>,
+}
+
+impl PartMap {
+    /// Parts of the given lengths, placed end to end.
+    pub fn from_lens(lens: impl IntoIterator<Item = usize>) -> Self {
+        let mut end = 0;
+        let ends = lens
+            .into_iter()
+            .map(|len| {
+                end += len;
+                end
+            })
+            .collect();
+        Self { ends }
+    }
+
+    pub fn part_count(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// Offset of the first byte of part `index`.
+    pub fn start(&self, index: usize) -> usize {
+        index.checked_sub(1).map_or(0, |i| self.ends[i])
+    }
+
+    /// The part holding `offset` and the offset within it, or `None` past
+    /// the end. Empty parts hold no offsets, so they are never named.
+    pub fn locate(&self, offset: usize) -> Option<(usize, usize)> {
+        let index = self.ends.partition_point(|&end| end <= offset);
+        (index < self.ends.len()).then(|| (index, offset - self.start(index)))
+    }
+}
+
+/// Several haystacks searched as one, end to end, such as the pieces of a
+/// split archive. The parts are copied into one buffer, because matches may
+/// span the joins and the searchers need contiguous bytes.
+pub struct Concat {
+    bytes: Vec<u8>,
+    parts: PartMap,
+}
+
+impl Concat {
+    pub fn new<H: Haystack>(parts: &[H]) -> Self {
+        let total = parts.iter().map(|p| p.as_bytes().len()).sum();
+        let mut bytes = Vec::with_capacity(total);
+        for part in parts {
+            bytes.extend_from_slice(part.as_bytes());
+        }
+        let parts = PartMap::from_lens(parts.iter().map(|p| p.as_bytes().len()));
+        Self { bytes, parts }
+    }
+
+    pub fn parts(&self) -> &PartMap {
+        &self.parts
+    }
+
+    /// The joined bytes and where each part lies in them.
+    pub fn into_parts(self) -> (Vec<u8>, PartMap) {
+        (self.bytes, self.parts)
+    }
+}
+
+impl Haystack for Concat {
+    fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Endianness {
     BigEndian,
     LittleEndian,
 }
 
+impl Endianness {
+    /// Reads a 1 to 8 byte integer from `bytes`, sign-extending it when `signed`.
+    pub fn read_int(self, bytes: &[u8], signed: bool) -> Option<i128> {
+        if bytes.is_empty() || bytes.len() > 8 {
+            return None;
+        }
+        let fold = |acc: u64, &b: &u8| (acc << 8) | b as u64;
+        let raw = match self {
+            Endianness::BigEndian => bytes.iter().fold(0, fold),
+            Endianness::LittleEndian => bytes.iter().rev().fold(0, fold),
+        };
+        if signed {
+            let shift = 64 - 8 * bytes.len() as u32;
+            Some((((raw << shift) as i64) >> shift) as i128)
+        } else {
+            Some(raw as i128)
+        }
+    }
+
+    pub fn other(self) -> Self {
+        match self {
+            Endianness::BigEndian => Endianness::LittleEndian,
+            Endianness::LittleEndian => Endianness::BigEndian,
+        }
+    }
+
+    fn short_name(self) -> &'static str {
+        match self {
+            Endianness::BigEndian => "BE",
+            Endianness::LittleEndian => "LE",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum Needle<'n> {
     U8(u8),
@@ -115,24 +235,94 @@ impl<'n> From<&'n [u8]> for Needle<'n> {
     }
 }
 
+/// The value and the bytes it is searched as, e.g. `u16 LE 0x1234 = [34 12]`.
+impl std::fmt::Display for Needle<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Needle::*;
+        match *self {
+            U8(v) => write!(f, "u8 0x{:X}", v)?,
+            I8(v) => write!(f, "i8 {}", v)?,
+            U16(e, v) => write!(f, "u16 {} 0x{:X}", e.short_name(), v)?,
+            I16(e, v) => write!(f, "i16 {} {}", e.short_name(), v)?,
+            U32(e, v) => write!(f, "u32 {} 0x{:X}", e.short_name(), v)?,
+            I32(e, v) => write!(f, "i32 {} {}", e.short_name(), v)?,
+            U64(e, v) => write!(f, "u64 {} 0x{:X}", e.short_name(), v)?,
+            I64(e, v) => write!(f, "i64 {} {}", e.short_name(), v)?,
+            Bytes(v) => write!(f, "{} bytes", v.len())?,
+            Str(v) => write!(f, "string {:?}", v)?,
+        }
+        // Only an empty string or byte slice has no bytes to show
+        let preview =
+            NeedleOwned::try_from(*self).map_or_else(|_| String::new(), |n| n.hex_preview());
+        write!(f, " = [{}]", preview)
+    }
+}
+
+/// An empty needle was given; there is nothing to search for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmptyNeedle;
+
+impl std::fmt::Display for EmptyNeedle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("nothing to search for")
+    }
+}
+
+impl std::error::Error for EmptyNeedle {}
+
+/// The bytes searched for; never empty, since an empty needle would match
+/// at every offset.
+#[derive(Clone)]
 pub struct NeedleOwned {
     needle: Box<[u8]>,
 }
 
 impl NeedleOwned {
-    pub fn from_data<T: Into<Box<[u8]>>>(data: T) -> Self {
-        Self {
-            needle: data.into(),
+    pub fn from_data<T: Into<Box<[u8]>>>(data: T) -> Result<Self, EmptyNeedle> {
+        let needle = data.into();
+        if needle.is_empty() {
+            return Err(EmptyNeedle);
         }
+        Ok(Self { needle })
     }
 
     pub fn byte_length(&self) -> usize {
         self.needle.len()
     }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.needle
+    }
+
+    /// The bytes in reverse order, which is an integer needle in the other
+    /// byte order.
+    pub fn reversed(&self) -> Self {
+        let mut needle = self.needle.clone();
+        needle.reverse();
+        Self { needle }
+    }
+
+    /// The first `PREVIEW_LEN` bytes in hex, e.g. `34 12`, noting the full
+    /// length of longer needles.
+    pub fn hex_preview(&self) -> String {
+        const PREVIEW_LEN: usize = 16;
+        let shown = &self.needle[..self.needle.len().min(PREVIEW_LEN)];
+        let mut preview = shown
+            .iter()
+            .map(|b| format!("{:02X}", b))
+            .collect::<Vec<_>>()
+            .join(" ");
+        if self.needle.len() > PREVIEW_LEN {
+            preview.push_str(&format!(" … ({} bytes)", self.needle.len()));
+        }
+        preview
+    }
 }
 
-impl<'n> From<Needle<'n>> for NeedleOwned {
-    fn from(value: Needle<'n>) -> Self {
+impl<'n> TryFrom<Needle<'n>> for NeedleOwned {
+    type Error = EmptyNeedle;
+
+    fn try_from(value: Needle<'n>) -> Result<Self, EmptyNeedle> {
         use Endianness::BigEndian as BE;
         use Endianness::LittleEndian as LE;
         use Needle::*;
@@ -154,62 +344,358 @@ impl<'n> From<Needle<'n>> for NeedleOwned {
             I64(BE, v) => Box::new(v.to_be_bytes()),
             I64(LE, v) => Box::new(v.to_le_bytes()),
         };
-        Self { needle }
+        Self::from_data(needle)
     }
 }
 
-pub struct AsyncSearch {
+/// Where and how `AsyncSearch` looks for a needle. The default searches the
+/// whole haystack for every match, byte for byte, e.g.
+/// `SearchOptions::new().range(0x100..0x200).alignment(4).limit(10)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchOptions {
+    range: Option<Range<usize>>,
+    alignment: usize,
+    limit: Option<usize>,
+    case_fold: bool,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SearchOptions {
+    pub fn new() -> Self {
+        Self {
+            range: None,
+            alignment: 1,
+            limit: None,
+            case_fold: false,
+        }
+    }
+
+    /// Searches only this part of the haystack; matches must fit inside it.
+    /// Offsets are still reported from the start of the haystack.
+    pub fn range(mut self, range: Range<usize>) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    /// Only reports matches at multiples of `alignment`. 0 is taken as 1.
+    pub fn alignment(mut self, alignment: usize) -> Self {
+        self.alignment = alignment.max(1);
+        self
+    }
+
+    /// Stops after `limit` matches.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Treats ASCII letters as equal regardless of case.
+    pub fn case_fold(mut self, case_fold: bool) -> Self {
+        self.case_fold = case_fold;
+        self
+    }
+}
+
+/// First match of `needle` in `haystack` with ASCII case ignored.
+fn find_folded(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    let first = needle[0];
+    memchr::memchr2_iter(
+        first.to_ascii_lowercase(),
+        first.to_ascii_uppercase(),
+        haystack,
+    )
+    .find(|&i| {
+        haystack[i..]
+            .get(..needle.len())
+            .is_some_and(|window| window.eq_ignore_ascii_case(needle))
+    })
+}
+
+/// Offsets of the non-overlapping matches of `needle` in `haystack` that
+/// `options` lets through. A needle longer than the haystack finishes at
+/// once without searching.
+fn find_all<'a>(
+    haystack: &'a [u8],
+    needle: &'a [u8],
+    options: &SearchOptions,
+) -> impl Iterator<Item = usize> + 'a {
+    let end = options
+        .range
+        .as_ref()
+        .map_or(haystack.len(), |r| r.end.min(haystack.len()));
+    let start = options.range.as_ref().map_or(0, |r| r.start.min(end));
+    let haystack = &haystack[..end];
+    let finder = memmem::Finder::new(needle);
+    let case_fold = options.case_fold;
+    let alignment = options.alignment;
+    let mut pos = start;
+    let matches = std::iter::from_fn(move || {
+        loop {
+            let tail = haystack.get(pos..)?;
+            let found = if case_fold {
+                find_folded(tail, needle)
+            } else {
+                finder.find(tail)
+            };
+            let offset = pos + found?;
+            if offset.is_multiple_of(alignment) {
+                pos = offset + needle.len();
+                return Some(offset);
+            }
+            // An unaligned match may hide an aligned one overlapping it
+            pos = offset + 1;
+        }
+    });
+    matches.take(options.limit.unwrap_or(usize::MAX))
+}
+
+/// Matches of any of `needles` in offset order, as the offset and the index
+/// of the needle. Each needle matches as in `find_all`, and `options.limit`
+/// caps the matches of all of them together.
+fn find_any<'a>(
+    haystack: &'a [u8],
+    needles: &'a [NeedleOwned],
+    options: &SearchOptions,
+) -> impl Iterator<Item = (usize, usize)> + 'a {
+    let mut streams: Vec<_> = needles
+        .iter()
+        .map(|n| find_all(haystack, &n.needle, options).peekable())
+        .collect();
+    let merged = std::iter::from_fn(move || {
+        // Ties go to the needle listed first
+        let (index, _) = streams
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, s)| s.peek().map(|&offset| (i, offset)))
+            .min_by_key(|&(i, offset)| (offset, i))?;
+        streams[index].next().map(|offset| (offset, index))
+    });
+    merged.take(options.limit.unwrap_or(usize::MAX))
+}
+
+/// Number of matches of `needle` in `haystack`, found without collecting
+/// or sending their offsets. Reports the same matches as `AsyncSearch`.
+pub fn count<H: Haystack>(haystack: &H, needle: &NeedleOwned) -> usize {
+    find_all(
+        haystack.as_bytes(),
+        &needle.needle,
+        &SearchOptions::default(),
+    )
+    .count()
+}
+
+/// Matches of `needle` in `haystack`, counted a chunk at a time so a worker
+/// can stop between chunks. Together they are what `count` reports.
+pub fn count_in_chunks<'a>(
+    haystack: &'a [u8],
+    needle: &'a NeedleOwned,
+) -> impl Iterator<Item = usize> + 'a {
+    // Small enough to cancel promptly, large enough to keep the overhead low
+    const CHUNK: usize = 16 << 20;
+    count_chunks(haystack, &needle.needle, CHUNK)
+}
+
+/// Matches of `needle` counted one `chunk`-byte stretch of `haystack` at a
+/// time, each in the stretch it starts in. Together they are what `count`
+/// reports.
+fn count_chunks<'a>(
+    haystack: &'a [u8],
+    needle: &'a [u8],
+    chunk: usize,
+) -> impl Iterator<Item = usize> + 'a {
+    let mut pos = 0;
+    std::iter::from_fn(move || {
+        if pos >= haystack.len() {
+            return None;
+        }
+        let chunk_end = pos.saturating_add(chunk).min(haystack.len());
+        // Wide enough for a match starting before `chunk_end`, but not after
+        let end = chunk_end
+            .saturating_add(needle.len() - 1)
+            .min(haystack.len());
+        let options = SearchOptions::new().range(pos..end);
+        let mut found = 0;
+        for offset in find_all(haystack, needle, &options) {
+            found += 1;
+            pos = offset + needle.len();
+        }
+        pos = pos.max(chunk_end);
+        Some(found)
+    })
+}
+
+/// Counts the matches of all of `needles` on a worker thread, as `count`
+/// would. A partial count is streamed for each stretch searched, so the
+/// count can show progress and be cancelled partway.
+pub fn count_async<H: Haystack>(haystack: H, needles: Vec<NeedleOwned>) -> AsyncStream<usize> {
+    AsyncStream::spawn(64, move |tx| {
+        let hs = haystack.as_bytes();
+        for needle in &needles {
+            for found in count_in_chunks(hs, needle) {
+                if tx.send(found).is_err() {
+                    return;
+                }
+            }
+        }
+    })
+}
+
+/// First match of `needle` starting at or after `from`, found on the
+/// calling thread.
+pub fn find_next<H: Haystack>(haystack: &H, needle: &NeedleOwned, from: usize) -> Option<usize> {
+    let tail = haystack.as_bytes().get(from..)?;
+    memmem::find(tail, &needle.needle).map(|i| from + i)
+}
+
+/// First offset at or after `offset` where `haystack` stops repeating
+/// `value`, stepping one needle length at a time. A trailing window shorter
+/// than the needle counts as differing. `None` if the rest of the haystack
+/// is all `value`.
+pub fn find_first_differing<H: Haystack>(
+    haystack: &H,
+    offset: usize,
+    value: &NeedleOwned,
+) -> Option<usize> {
+    let tail = haystack.as_bytes().get(offset..)?;
+    let value = value.as_bytes();
+    let found = match value {
+        [byte] => tail.iter().position(|b| b != byte),
+        _ => tail
+            .chunks(value.len())
+            .position(|chunk| chunk != value)
+            .map(|i| i * value.len()),
+    };
+    found.map(|i| offset + i)
+}
+
+/// Runs of at least `min_len` copies of `byte` in `haystack`, as
+/// `(offset, length)` in ascending order, such as padding or unused space.
+/// Each run is as long as it goes, so runs never touch.
+pub fn find_runs(haystack: &[u8], byte: u8, min_len: usize) -> Vec<(usize, usize)> {
+    runs(haystack, byte, min_len).collect()
+}
+
+/// The runs `find_runs` lists, found one at a time.
+fn runs(haystack: &[u8], byte: u8, min_len: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let min_len = min_len.max(1);
+    let mut pos = 0;
+    std::iter::from_fn(move || {
+        while let Some(i) = memchr::memchr(byte, &haystack[pos..]) {
+            let start = pos + i;
+            let len = haystack[start..]
+                .iter()
+                .position(|&b| b != byte)
+                .unwrap_or(haystack.len() - start);
+            pos = start + len;
+            if len >= min_len {
+                return Some((start, len));
+            }
+        }
+        None
+    })
+}
+
+/// Runs of differing bytes between `a` and `b`, as `(offset, length)` in
+/// ascending order. The tail of the longer input counts as one differing run.
+pub fn diff(a: &[u8], b: &[u8]) -> Vec<(usize, usize)> {
+    diff_runs(a, b).collect()
+}
+
+/// The runs `diff` lists, found one at a time.
+fn diff_runs<'a>(a: &'a [u8], b: &'a [u8]) -> impl Iterator<Item = (usize, usize)> + 'a {
+    // Equal chunks are skipped with one slice compare; only differing
+    // chunks are walked byte by byte
+    const CHUNK: usize = 4096;
+    let common = a.len().min(b.len());
+    let longer = a.len().max(b.len());
+    let mut pos = 0;
+    std::iter::from_fn(move || {
+        while pos < common {
+            let end = (pos + CHUNK).min(common);
+            let Some(i) = (pos..end).find(|&i| a[i] != b[i]) else {
+                pos = end;
+                continue;
+            };
+            let mut run_end = (i..common).find(|&j| a[j] == b[j]).unwrap_or(common);
+            // A run reaching the end of the shorter input goes on into the tail
+            if run_end == common {
+                run_end = longer;
+            }
+            pos = run_end;
+            return Some((i, run_end - i));
+        }
+        if pos == common && longer > common {
+            pos = longer;
+            return Some((common, longer - common));
+        }
+        None
+    })
+}
+
+/// Runs a producer on its own thread and streams the items it sends, so
+/// the consumer can collect them a few at a time between frames.
+/// `AsyncSearch` and the other background scans are built on it.
+pub struct AsyncStream<T> {
     join_handle: JoinHandle<()>,
-    receiver: mpsc::Receiver<usize>,
+    receiver: mpsc::Receiver<T>,
+    // Set by the thread once it returns normally, so a disconnect without
+    // it means the thread panicked
+    completed: Arc<AtomicBool>,
 }
 
+#[derive(Debug, PartialEq, Eq)]
 pub enum SearchState {
     Pending,
     Finished,
+    /// The search thread panicked; results received so far may be incomplete.
+    Panicked,
 }
 
-impl AsyncSearch {
-    pub fn create_from_owned<H>(haystack: H, needle: NeedleOwned) -> Self
+impl<T: Send + 'static> AsyncStream<T> {
+    /// Starts `produce` on a new thread. Up to `capacity` items are buffered
+    /// between it and the consumer; past that, sending blocks, so a consumer
+    /// that stops reading also stops the producer from growing memory. A
+    /// failed send means the stream was cancelled, and the producer should
+    /// return.
+    pub fn spawn<F>(capacity: usize, produce: F) -> Self
     where
-        H: Haystack,
+        F: FnOnce(&mpsc::SyncSender<T>) + Send + 'static,
     {
-        let (tx, rx) = mpsc::channel();
+        let (tx, rx) = mpsc::sync_channel(capacity);
+        let completed = Arc::new(AtomicBool::new(false));
+        let thread_completed = completed.clone();
         let join_handle = thread::spawn(move || {
-            let hs = haystack.as_bytes();
-            let it = memmem::find_iter(hs, &needle.needle);
-            for n in it {
-                if tx.send(n).is_err() {
-                    break;
-                }
-            }
+            produce(&tx);
+            // Stored before `tx` drops, so the receiver sees it on disconnect
+            thread_completed.store(true, Ordering::Release);
         });
         Self {
             join_handle,
             receiver: rx,
+            completed,
         }
     }
 
-    pub fn create<'s, H, S>(haystack: H, s: S) -> Self
-    where
-        H: Haystack,
-        S: Into<Needle<'s>>,
-    {
-        let s_owned: NeedleOwned = s.into().into();
-        Self::create_from_owned(haystack, s_owned)
-    }
-
-    pub fn try_get(&self) -> Result<usize, SearchState> {
+    pub fn try_get(&self) -> Result<T, SearchState> {
         self.receiver
             .try_recv()
             .map_err(|try_recv_err| match try_recv_err {
                 mpsc::TryRecvError::Empty => SearchState::Pending,
-                mpsc::TryRecvError::Disconnected => SearchState::Finished,
+                mpsc::TryRecvError::Disconnected if self.completed.load(Ordering::Acquire) => {
+                    SearchState::Finished
+                }
+                mpsc::TryRecvError::Disconnected => SearchState::Panicked,
             })
     }
 
     pub fn drain<F>(&self, mut callback: F) -> SearchState
     where
-        F: FnMut(usize) -> (),
+        F: FnMut(T),
     {
         loop {
             match self.try_get() {
@@ -219,6 +705,17 @@ impl AsyncSearch {
         }
     }
 
+    /// Blocks for the next item, `None` once the producer has stopped.
+    pub fn recv(&self) -> Option<T> {
+        self.receiver.recv().ok()
+    }
+
+    /// Blocks for each item in turn until the producer stops, as
+    /// `AsyncSearch::iter` does for matches.
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        std::iter::from_fn(|| self.recv())
+    }
+
     pub fn cancel(self) -> EyreReult<()> {
         drop(self.receiver);
         self.join_handle
@@ -227,32 +724,221 @@ impl AsyncSearch {
     }
 }
 
+/// One hit streamed by `AsyncSearch`: where it starts and how many bytes it
+/// covers. Fixed needles always report their own length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    pub offset: usize,
+    pub len: usize,
+    /// Index of the needle found, for a search for several.
+    pub pattern: usize,
+}
+
+pub struct AsyncSearch {
+    stream: AsyncStream<Match>,
+}
+
+impl AsyncSearch {
+    /// Offsets buffered between the search thread and the consumer. The
+    /// thread blocks once this many are waiting, so a consumer that stops
+    /// reading also stops the search from growing memory.
+    const CHANNEL_CAPACITY: usize = 1 << 16;
+
+    pub fn create_from_owned<H>(haystack: H, needle: NeedleOwned) -> Self
+    where
+        H: Haystack,
+    {
+        Self::create_with_options(haystack, needle, SearchOptions::default())
+    }
+
+    /// Starts a search limited or relaxed by `options`; `create` and
+    /// `create_from_owned` search with the defaults.
+    pub fn create_with_options<H>(haystack: H, needle: NeedleOwned, options: SearchOptions) -> Self
+    where
+        H: Haystack,
+    {
+        Self::create_multi(haystack, vec![needle], options)
+    }
+
+    /// Searches for all of `needles` at once, streaming their matches in
+    /// offset order.
+    pub fn create_multi<H>(haystack: H, needles: Vec<NeedleOwned>, options: SearchOptions) -> Self
+    where
+        H: Haystack,
+    {
+        let stream = AsyncStream::spawn(Self::CHANNEL_CAPACITY, move |tx| {
+            let hs = haystack.as_bytes();
+            for (offset, pattern) in find_any(hs, &needles, &options) {
+                let len = needles[pattern].byte_length();
+                if tx
+                    .send(Match {
+                        offset,
+                        len,
+                        pattern,
+                    })
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+        Self { stream }
+    }
+
+    /// Streams the runs `find_runs` lists as matches, each covering its run,
+    /// so they can be listed and stepped through like search results.
+    pub fn create_runs<H>(haystack: H, byte: u8, min_len: usize) -> Self
+    where
+        H: Haystack,
+    {
+        let stream = AsyncStream::spawn(Self::CHANNEL_CAPACITY, move |tx| {
+            for (offset, len) in runs(haystack.as_bytes(), byte, min_len) {
+                let found = Match {
+                    offset,
+                    len,
+                    pattern: 0,
+                };
+                if tx.send(found).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { stream }
+    }
+
+    /// Streams the runs `diff` lists between `haystack` and `other` as
+    /// matches, each covering its run.
+    pub fn create_diff<H, O>(haystack: H, other: O) -> Self
+    where
+        H: Haystack,
+        O: Haystack,
+    {
+        let stream = AsyncStream::spawn(Self::CHANNEL_CAPACITY, move |tx| {
+            for (offset, len) in diff_runs(haystack.as_bytes(), other.as_bytes()) {
+                let found = Match {
+                    offset,
+                    len,
+                    pattern: 0,
+                };
+                if tx.send(found).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { stream }
+    }
+
+    pub fn create<'s, H, S>(haystack: H, s: S) -> Result<Self, EmptyNeedle>
+    where
+        H: Haystack,
+        S: Into<Needle<'s>>,
+    {
+        let s_owned = NeedleOwned::try_from(s.into())?;
+        Ok(Self::create_from_owned(haystack, s_owned))
+    }
+
+    pub fn try_get(&self) -> Result<Match, SearchState> {
+        self.stream.try_get()
+    }
+
+    pub fn drain<F>(&self, callback: F) -> SearchState
+    where
+        F: FnMut(Match),
+    {
+        self.stream.drain(callback)
+    }
+
+    /// Blocks for each match in turn until the search ends, for callers
+    /// that can wait instead of polling `try_get`. Once it returns `None`,
+    /// `try_get` tells whether the search finished or panicked.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter { search: self }
+    }
+
+    pub fn cancel(self) -> EyreReult<()> {
+        self.stream.cancel()
+    }
+}
+
+/// Blocking iterator over the matches of an `AsyncSearch`, from `AsyncSearch::iter`.
+pub struct Iter<'a> {
+    search: &'a AsyncSearch,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = Match;
+
+    fn next(&mut self) -> Option<Match> {
+        self.search.stream.recv()
+    }
+}
+
+impl<'a> IntoIterator for &'a AsyncSearch {
+    type Item = Match;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::TempPath;
+
+    #[test]
+    fn test_needle_display() {
+        use Endianness::*;
+        assert_eq!(
+            Needle::U16(LittleEndian, 0x1234).to_string(),
+            "u16 LE 0x1234 = [34 12]"
+        );
+        assert_eq!(
+            Needle::I32(BigEndian, -2).to_string(),
+            "i32 BE -2 = [FF FF FF FE]"
+        );
+        assert_eq!(Needle::U8(0x2A).to_string(), "u8 0x2A = [2A]");
+        assert_eq!(Needle::Str("AB").to_string(), "string \"AB\" = [41 42]");
+        assert_eq!(Needle::Bytes(&[]).to_string(), "0 bytes = []");
+
+        let le: NeedleOwned = Needle::U16(LittleEndian, 0x1234).try_into().unwrap();
+        let be: NeedleOwned = Needle::U16(BigEndian, 0x1234).try_into().unwrap();
+        assert_eq!(le.reversed().as_bytes(), be.as_bytes());
+
+        let long = NeedleOwned::from_data(vec![0xAB; 20]).unwrap();
+        assert_eq!(
+            long.hex_preview(),
+            format!("{} … (20 bytes)", ["AB"; 16].join(" "))
+        );
+    }
 
     #[test]
     fn test_needle_owned_creation() {
         // Test basic types
-        let needle_u8: NeedleOwned = Needle::U8(42).into();
+        let needle_u8: NeedleOwned = Needle::U8(42).try_into().unwrap();
         assert_eq!(needle_u8.needle.as_ref(), &[42]);
 
-        let needle_i8: NeedleOwned = Needle::I8(-1).into();
+        let needle_i8: NeedleOwned = Needle::I8(-1).try_into().unwrap();
         assert_eq!(needle_i8.needle.as_ref(), &[255]); // -1 as u8 is 255
 
         // Test endianness
-        let needle_u16_le: NeedleOwned = Needle::U16(Endianness::LittleEndian, 0x1234).into();
+        let needle_u16_le: NeedleOwned = Needle::U16(Endianness::LittleEndian, 0x1234)
+            .try_into()
+            .unwrap();
         assert_eq!(needle_u16_le.needle.as_ref(), &[0x34, 0x12]);
 
-        let needle_u16_be: NeedleOwned = Needle::U16(Endianness::BigEndian, 0x1234).into();
+        let needle_u16_be: NeedleOwned = Needle::U16(Endianness::BigEndian, 0x1234)
+            .try_into()
+            .unwrap();
         assert_eq!(needle_u16_be.needle.as_ref(), &[0x12, 0x34]);
 
         // Test string
-        let needle_str: NeedleOwned = Needle::Str("hello").into();
+        let needle_str: NeedleOwned = Needle::Str("hello").try_into().unwrap();
         assert_eq!(needle_str.needle.as_ref(), b"hello");
 
         // Test bytes
-        let needle_bytes: NeedleOwned = Needle::Bytes(&[1, 2, 3, 4]).into();
+        let needle_bytes: NeedleOwned = Needle::Bytes(&[1, 2, 3, 4]).try_into().unwrap();
         assert_eq!(needle_bytes.needle.as_ref(), &[1, 2, 3, 4]);
     }
 
@@ -261,23 +947,305 @@ mod tests {
         let haystack = b"hello world hello universe";
         let needle = Needle::Str("hello");
 
-        let search = AsyncSearch::create(haystack.as_slice(), needle);
-
-        // Give it a moment to find results
-        std::thread::sleep(std::time::Duration::from_millis(10));
-
-        let mut results = Vec::new();
-        loop {
-            match search.try_get() {
-                Ok(offset) => results.push(offset),
-                Err(SearchState::Pending) => break,
-                Err(SearchState::Finished) => break,
-            }
-        }
+        let search = AsyncSearch::create(haystack.as_slice(), needle).unwrap();
+        let results = collect(search);
 
         // Should find "hello" at positions 0 and 12
         assert_eq!(results.len(), 2);
         assert!(results.contains(&0));
         assert!(results.contains(&12));
     }
+
+    #[test]
+    fn test_async_search_iter() {
+        let haystack = b"hello world hello universe".to_vec();
+        let search = AsyncSearch::create(haystack, Needle::Str("hello")).unwrap();
+        let offsets: Vec<usize> = search.iter().map(|m| m.offset).collect();
+        assert_eq!(offsets, [0, 12]);
+        assert_eq!(search.try_get(), Err(SearchState::Finished));
+        assert_eq!((&search).into_iter().next(), None);
+        assert!(search.cancel().is_ok());
+    }
+
+    #[test]
+    fn test_concat() {
+        let parts = [
+            b"xxab".to_vec(),
+            b"cd".to_vec(),
+            Vec::new(),
+            b"abcd".to_vec(),
+        ];
+        let concat = Concat::new(&parts);
+        assert_eq!(concat.as_bytes(), b"xxabcdabcd");
+        let map = concat.parts().clone();
+        assert_eq!(map.part_count(), 4);
+        assert_eq!(map.start(3), 6);
+
+        // A match may straddle the join between two parts
+        let search = AsyncSearch::create(concat, Needle::Str("abcd")).unwrap();
+        let found: Vec<_> = search.iter().map(|m| map.locate(m.offset)).collect();
+        assert_eq!(found, [Some((0, 2)), Some((3, 0))]);
+        assert_eq!(map.locate(5), Some((1, 1)));
+        assert_eq!(map.locate(10), None);
+        assert_eq!(PartMap::default().locate(0), None);
+    }
+
+    #[test]
+    fn test_read_int() {
+        use Endianness::*;
+        assert_eq!(LittleEndian.read_int(&[0x34, 0x12], false), Some(0x1234));
+        assert_eq!(BigEndian.read_int(&[0x12, 0x34], false), Some(0x1234));
+        assert_eq!(LittleEndian.read_int(&[0xFE, 0xFF], true), Some(-2));
+        assert_eq!(LittleEndian.read_int(&[0xFE, 0xFF], false), Some(0xFFFE));
+        assert_eq!(BigEndian.read_int(&[0x80], true), Some(-128));
+        assert_eq!(
+            LittleEndian.read_int(&[0xFF; 8], false),
+            Some(u64::MAX as i128)
+        );
+        assert_eq!(LittleEndian.read_int(&[0xFF; 8], true), Some(-1));
+        assert_eq!(LittleEndian.read_int(&[], false), None);
+        assert_eq!(LittleEndian.read_int(&[0; 9], false), None);
+    }
+
+    #[test]
+    fn test_count() {
+        let haystack = b"hello world hello universe".to_vec();
+        assert_eq!(
+            count(&haystack, &Needle::Str("hello").try_into().unwrap()),
+            2
+        );
+        assert_eq!(count(&haystack, &Needle::U8(b'o').try_into().unwrap()), 3);
+        assert_eq!(count(&haystack, &Needle::Str("xyz").try_into().unwrap()), 0);
+    }
+
+    #[test]
+    fn test_find_next() {
+        let haystack = b"hello world hello universe".to_vec();
+        let needle = Needle::Str("hello").try_into().unwrap();
+        assert_eq!(find_next(&haystack, &needle, 0), Some(0));
+        assert_eq!(find_next(&haystack, &needle, 1), Some(12));
+        assert_eq!(find_next(&haystack, &needle, 12), Some(12));
+        assert_eq!(find_next(&haystack, &needle, 13), None);
+        assert_eq!(find_next(&haystack, &needle, 100), None);
+    }
+
+    #[test]
+    fn test_find_first_differing() {
+        let haystack = vec![0, 0, 0, 0, 7, 0];
+        let zero = Needle::U8(0).try_into().unwrap();
+        assert_eq!(find_first_differing(&haystack, 0, &zero), Some(4));
+        assert_eq!(find_first_differing(&haystack, 4, &zero), Some(4));
+        assert_eq!(find_first_differing(&haystack, 5, &zero), None);
+        assert_eq!(find_first_differing(&haystack, 6, &zero), None);
+        assert_eq!(find_first_differing(&haystack, 7, &zero), None);
+
+        // Wider values step a whole window at a time
+        let fill = b"ABABABAx".to_vec();
+        let ab = Needle::Str("AB").try_into().unwrap();
+        assert_eq!(find_first_differing(&fill, 0, &ab), Some(6));
+        assert_eq!(find_first_differing(&fill, 1, &ab), Some(1));
+        assert_eq!(find_first_differing(&b"ABA".to_vec(), 0, &ab), Some(2));
+    }
+
+    #[test]
+    fn test_find_runs() {
+        let data = b"\0\0\0\0ab\0c\0\0\0\0\0";
+        assert_eq!(find_runs(data, 0, 4), [(0, 4), (8, 5)]);
+        assert_eq!(find_runs(data, 0, 1), [(0, 4), (6, 1), (8, 5)]);
+        assert_eq!(find_runs(data, 0, 0), find_runs(data, 0, 1));
+        assert_eq!(find_runs(data, 0, 6), []);
+        assert_eq!(find_runs(data, b'a', 1), [(4, 1)]);
+        assert_eq!(find_runs(b"", 0, 1), []);
+
+        let search = AsyncSearch::create_runs(data.to_vec(), 0, 4);
+        let runs: Vec<(usize, usize)> = search.iter().map(|m| (m.offset, m.len)).collect();
+        assert_eq!(runs, [(0, 4), (8, 5)]);
+    }
+
+    #[test]
+    fn test_diff() {
+        assert_eq!(diff(b"abcdef", b"abcdef"), []);
+        assert_eq!(diff(b"abcdef", b"aXXdeY"), [(1, 2), (5, 1)]);
+        assert_eq!(diff(b"abc", b"abcde"), [(3, 2)]);
+        assert_eq!(diff(b"abcde", b"abX"), [(2, 3)]);
+        assert_eq!(diff(b"", b"ab"), [(0, 2)]);
+
+        // Runs spanning a chunk boundary are joined
+        let a = vec![0u8; 10_000];
+        let mut b = a.clone();
+        b[4090..4100].fill(1);
+        assert_eq!(diff(&a, &b), [(4090, 10)]);
+        // As does a run reaching the tail of the longer input
+        assert_eq!(diff(b"abcd", b"abXYef"), [(2, 4)]);
+
+        let search = AsyncSearch::create_diff(b"abcdef".to_vec(), b"aXXdeY".to_vec());
+        let runs: Vec<(usize, usize)> = search.iter().map(|m| (m.offset, m.len)).collect();
+        assert_eq!(runs, [(1, 2), (5, 1)]);
+    }
+
+    #[test]
+    fn test_async_search_empty_haystack() {
+        let search = AsyncSearch::create(Vec::<u8>::new(), Needle::U8(0)).unwrap();
+        assert!(collect(search).is_empty());
+    }
+
+    /// Offsets of every match, once the search has run to its end.
+    fn collect(search: AsyncSearch) -> Vec<usize> {
+        let offsets = search.iter().map(|m| m.offset).collect();
+        assert_eq!(search.try_get(), Err(SearchState::Finished), "search thread panicked");
+        offsets
+    }
+
+    #[test]
+    fn test_needle_longer_than_haystack() {
+        let haystack = b"abcd".to_vec();
+        let needle = Needle::Str("abcdefghij");
+        assert_eq!(count(&haystack, &needle.try_into().unwrap()), 0);
+        assert!(collect(AsyncSearch::create(haystack, needle).unwrap()).is_empty());
+
+        let exact = Needle::Str("abcd");
+        assert_eq!(
+            collect(AsyncSearch::create(b"abcd".to_vec(), exact).unwrap()),
+            [0]
+        );
+    }
+
+    #[test]
+    fn test_matches_carry_needle_length() {
+        let search = AsyncSearch::create(b"xxhello".to_vec(), "hello").unwrap();
+        assert_eq!(
+            search.iter().next(),
+            Some(Match {
+                offset: 2,
+                len: 5,
+                pattern: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_search_multi() {
+        let needles = || {
+            [&[1u8, 0][..], &[2, 0], &[1, 0, 2]]
+                .map(|b| NeedleOwned::from_data(b).unwrap())
+                .to_vec()
+        };
+        let haystack = vec![2, 0, 1, 0, 2, 0, 1, 0];
+        let search =
+            AsyncSearch::create_multi(haystack.clone(), needles(), SearchOptions::default());
+        let mut found = Vec::new();
+        while search.drain(|m| found.push((m.offset, m.pattern, m.len))) == SearchState::Pending {
+            std::thread::yield_now();
+        }
+        assert_eq!(
+            found,
+            [(0, 1, 2), (2, 0, 2), (2, 2, 3), (4, 1, 2), (6, 0, 2)]
+        );
+
+        let needles = needles();
+        let limited = find_any(&haystack, &needles[..2], &SearchOptions::new().limit(3));
+        assert_eq!(limited.collect::<Vec<_>>(), [(0, 1), (2, 0), (4, 1)]);
+    }
+
+    #[test]
+    fn test_search_options() {
+        let haystack = b"abcABCxabcabc".to_vec();
+        let search = |options: SearchOptions| {
+            let needle = NeedleOwned::from_data(b"abc".to_vec()).unwrap();
+            collect(AsyncSearch::create_with_options(
+                haystack.clone(),
+                needle,
+                options,
+            ))
+        };
+        assert_eq!(search(SearchOptions::new()), [0, 7, 10]);
+        assert_eq!(search(SearchOptions::new().case_fold(true)), [0, 3, 7, 10]);
+        assert_eq!(search(SearchOptions::new().range(1..10)), [7]);
+        assert_eq!(search(SearchOptions::new().range(7..100)), [7, 10]);
+        assert_eq!(search(SearchOptions::new().range(20..30)), []);
+        assert_eq!(search(SearchOptions::new().alignment(5)), [0, 10]);
+        assert_eq!(search(SearchOptions::new().limit(2)), [0, 7]);
+
+        // Overlapping an unaligned match does not hide an aligned one
+        let needle = NeedleOwned::from_data(b"aa".to_vec()).unwrap();
+        let options = SearchOptions::new().alignment(2);
+        let search = AsyncSearch::create_with_options(b"xaaa".to_vec(), needle, options);
+        assert_eq!(collect(search), [2]);
+    }
+
+    #[test]
+    fn test_empty_needle_rejected() {
+        assert_eq!(NeedleOwned::from_data(Vec::new()).err(), Some(EmptyNeedle));
+        assert_eq!(
+            NeedleOwned::try_from(Needle::Str("")).err(),
+            Some(EmptyNeedle)
+        );
+        assert_eq!(
+            NeedleOwned::try_from(Needle::Bytes(&[])).err(),
+            Some(EmptyNeedle)
+        );
+        assert!(AsyncSearch::create(b"abcd".to_vec(), "").is_err());
+    }
+
+    #[test]
+    fn test_search_shares_mmap_with_reader() {
+        // Declared first so it outlives the mapping
+        let path = TempPath::new("mmap");
+        let mut data = vec![0u8; 1 << 20];
+        for offset in (0..data.len()).step_by(4096) {
+            data[offset..offset + 4].copy_from_slice(b"MARK");
+        }
+        std::fs::write(&path, &data).unwrap();
+        let file = std::fs::File::open(&path).unwrap();
+        let mmap = Arc::new(unsafe { Mmap::map(&file) }.unwrap());
+
+        let needle = NeedleOwned::try_from(Needle::Str("MARK")).unwrap();
+        let search = AsyncSearch::create_from_owned(mmap.clone(), needle.clone());
+        // Read the same mapping on this thread while the search runs
+        let mut offsets = Vec::new();
+        for m in &search {
+            assert_eq!(count(&mmap, &needle), 256);
+            offsets.push(m.offset);
+        }
+        assert_eq!(search.try_get(), Err(SearchState::Finished));
+        assert_eq!(offsets, (0..data.len()).step_by(4096).collect::<Vec<_>>());
+        assert_eq!(&mmap[..], &data[..]);
+    }
+
+    #[test]
+    fn test_count_chunks() {
+        let haystack = b"aaaaaaaaaXabXab".to_vec();
+        for needle in [&b"aa"[..], b"Xab", b"a", b"b"] {
+            let owned = NeedleOwned::try_from(Needle::Bytes(needle)).unwrap();
+            let total = count(&haystack, &owned);
+            // Stretches that split matches must not lose or double count them
+            for chunk in 1..=haystack.len() {
+                assert_eq!(count_chunks(&haystack, needle, chunk).sum::<usize>(), total);
+            }
+        }
+
+        let needles = vec![
+            NeedleOwned::try_from(Needle::Str("aa")).unwrap(),
+            NeedleOwned::try_from(Needle::Str("X")).unwrap(),
+        ];
+        let counting = count_async(haystack, needles);
+        let total: usize = counting.iter().sum();
+        assert_eq!(total, 6);
+        assert_eq!(counting.try_get(), Err(SearchState::Finished));
+    }
+
+    #[test]
+    fn test_async_search_reports_panic() {
+        struct Broken;
+        impl Haystack for Broken {
+            fn as_bytes(&self) -> &[u8] {
+                panic!("broken haystack")
+            }
+        }
+
+        let search = AsyncSearch::create(Broken, Needle::U8(0)).unwrap();
+        assert_eq!(search.iter().next(), None);
+        assert_eq!(search.try_get(), Err(SearchState::Panicked));
+        assert!(search.cancel().is_err());
+    }
 }
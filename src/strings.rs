@@ -0,0 +1,155 @@
+use strum_macros::EnumIter;
+
+use crate::search::{AsyncStream, Haystack};
+
+/// Which characters make up the text runs `find_strings` reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+pub enum StringEncoding {
+    /// Printable ASCII and tab, like GNU `strings`.
+    Ascii,
+    /// Printable ASCII, tab and any other printable UTF-8 character.
+    Utf8,
+}
+
+impl std::fmt::Display for StringEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StringEncoding::Ascii => write!(f, "ASCII"),
+            StringEncoding::Utf8 => write!(f, "UTF-8"),
+        }
+    }
+}
+
+/// A run of text: where it starts, how many bytes it covers, and the start
+/// of it as text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoundString {
+    pub offset: usize,
+    pub len: usize,
+    pub text: String,
+}
+
+impl FoundString {
+    /// Characters kept in `text`; longer runs are cut, the length still covers all of it.
+    pub const MAX_TEXT_CHARS: usize = 200;
+}
+
+/// Byte length of the printable character at the start of `rest`, if it starts with one.
+fn printable_len(rest: &[u8], encoding: StringEncoding) -> Option<usize> {
+    let first = *rest.first()?;
+    if first == b'\t' || (0x20..=0x7E).contains(&first) {
+        return Some(1);
+    }
+    if encoding == StringEncoding::Ascii || first < 0x80 {
+        return None;
+    }
+    let len = match first {
+        0xC2..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF4 => 4,
+        _ => return None,
+    };
+    let ch = std::str::from_utf8(rest.get(..len)?).ok()?.chars().next()?;
+    (!ch.is_control()).then_some(len)
+}
+
+/// Runs of at least `min_chars` printable characters in `data`, in offset
+/// order, like the `strings` utility.
+pub fn find_strings(
+    data: &[u8],
+    min_chars: usize,
+    encoding: StringEncoding,
+) -> impl Iterator<Item = FoundString> + '_ {
+    let min_chars = min_chars.max(1);
+    let mut pos = 0;
+    std::iter::from_fn(move || {
+        while pos < data.len() {
+            let start = pos;
+            let mut chars = 0;
+            while let Some(len) = printable_len(&data[pos..], encoding) {
+                pos += len;
+                chars += 1;
+            }
+            if chars == 0 {
+                pos += 1;
+                continue;
+            }
+            if chars >= min_chars {
+                // Only whole printable characters were taken, so this is valid UTF-8
+                let text = String::from_utf8_lossy(&data[start..pos]);
+                return Some(FoundString {
+                    offset: start,
+                    len: pos - start,
+                    text: text.chars().take(FoundString::MAX_TEXT_CHARS).collect(),
+                });
+            }
+        }
+        None
+    })
+}
+
+/// Strings streamed from a worker thread, as `AsyncSearch` streams matches.
+pub type AsyncStrings = AsyncStream<FoundString>;
+
+// Strings buffered between the thread and the consumer, which also bounds
+// memory when the consumer stops reading
+const CHANNEL_CAPACITY: usize = 1 << 14;
+
+/// Runs `find_strings` on its own thread and streams what it finds.
+pub fn scan_strings<H: Haystack>(
+    haystack: H,
+    min_chars: usize,
+    encoding: StringEncoding,
+) -> AsyncStrings {
+    AsyncStream::spawn(CHANNEL_CAPACITY, move |tx| {
+        for found in find_strings(haystack.as_bytes(), min_chars, encoding) {
+            if tx.send(found).is_err() {
+                break;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::SearchState;
+
+    fn offsets(data: &[u8], min_chars: usize, encoding: StringEncoding) -> Vec<(usize, usize)> {
+        find_strings(data, min_chars, encoding)
+            .map(|s| (s.offset, s.len))
+            .collect()
+    }
+
+    #[test]
+    fn test_find_strings() {
+        let data = b"\0\0abcd\x01ab\xFFhello\tworld\0";
+        assert_eq!(offsets(data, 4, StringEncoding::Ascii), [(2, 4), (10, 11)]);
+        assert_eq!(
+            offsets(data, 2, StringEncoding::Ascii),
+            [(2, 4), (7, 2), (10, 11)]
+        );
+        let found: Vec<String> = find_strings(data, 4, StringEncoding::Ascii)
+            .map(|s| s.text)
+            .collect();
+        assert_eq!(found, ["abcd", "hello\tworld"]);
+
+        // Multi-byte characters count once, and only in UTF-8 mode
+        let data = "\0café\0日本語\0".as_bytes();
+        assert_eq!(offsets(data, 3, StringEncoding::Ascii), [(1, 3)]);
+        assert_eq!(offsets(data, 3, StringEncoding::Utf8), [(1, 5), (7, 9)]);
+        // A truncated sequence at the end is not text
+        assert_eq!(offsets(b"abc\xE6\x97", 3, StringEncoding::Utf8), [(0, 3)]);
+        assert_eq!(offsets(b"abc", 0, StringEncoding::Ascii), [(0, 3)]);
+    }
+
+    #[test]
+    fn test_async_strings() {
+        let data = b"one\0two\0three".to_vec();
+        let scan = scan_strings(data, 3, StringEncoding::Ascii);
+        let found: Vec<String> = scan.iter().map(|s| s.text).collect();
+        assert_eq!(scan.try_get().err(), Some(SearchState::Finished));
+        assert_eq!(found, ["one", "two", "three"]);
+        assert!(scan.cancel().is_ok());
+    }
+}
@@ -0,0 +1,39 @@
+//! Fixtures shared by the unit tests.
+
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+/// A path in the system temp directory, unique to this test process.
+/// Whatever the test creates there, file or directory, is removed on drop,
+/// so a failing assertion does not leave it behind.
+pub struct TempPath(PathBuf);
+
+impl TempPath {
+    pub fn new(name: &str) -> Self {
+        Self(std::env::temp_dir().join(format!("rsearch-{}-{}", name, std::process::id())))
+    }
+}
+
+impl Deref for TempPath {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempPath {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        if self.0.is_dir() {
+            let _ = std::fs::remove_dir_all(&self.0);
+        } else {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+}
@@ -1,109 +1,615 @@
-use crate::search::{AsyncSearch, Needle, NeedleOwned, SearchState};
+use crate::search::{self, AsyncSearch, Needle, NeedleOwned, SearchOptions};
 use crate::ui;
 use crate::ui::components::{
-    DataInspector, FilePanel, HexViewer, SearchControlPanel, SearchResultsPanel,
+    BatchAction, BatchSearchPanel, BitViewPanel, DataInspector, HexViewer, Highlights,
+    ResultsAction, ScanFilter, ScanPanel, SearchControlPanel, SearchRequest, SelectionStyle,
+    StructPanel, ToastLevel, Toasts,
 };
-use crate::ui::util::{Encoding, SearchType, Selection};
+use crate::ui::document::{CurrentSearch, Document, EndiannessProbe, FilePositions};
+use crate::ui::int_parse::IntParser;
+use crate::ui::util::{OffsetBase, SearchType, Selection};
 use eframe::egui;
 use egui_extras::{Size, StripBuilder};
-
-enum CurrentSearch {
-    Empty,
-    Searching(usize, AsyncSearch),
-    Finished(usize),
-    // usize is the byte length of this search
-}
-
-impl CurrentSearch {
-    pub fn take(&mut self) -> CurrentSearch {
-        let mut res = CurrentSearch::Empty;
-        std::mem::swap(&mut res, self);
-        res
-    }
-    pub fn finish(&mut self) {
-        match self {
-            CurrentSearch::Empty => {}
-            CurrentSearch::Searching(i, _) => *self = CurrentSearch::Finished(*i),
-            CurrentSearch::Finished(_) => {}
-        }
-    }
-}
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use strum::IntoEnumIterator;
 
 pub struct BinarySearchApp {
-    // UI components
-    file_panel: FilePanel,
+    // Open files, one per tab, and the index of the one shown
+    documents: Vec<Document>,
+    active: usize,
+    // UI components shared by all tabs
     search_control_panel: SearchControlPanel,
-    search_results_panel: SearchResultsPanel,
-    hex_viewer: HexViewer,
     data_inspector: DataInspector,
-    selection: Option<Selection>,
-    last_selection: Option<Selection>,
-    // Search state
-    // current_search: byte length + search handle
-    current_search: CurrentSearch,
+    bit_view_panel: BitViewPanel,
+    struct_panel: StructPanel,
+    scan_panel: ScanPanel,
+    batch_search_panel: BatchSearchPanel,
+    // Selection and scroll of files that were open, restored when reopened
+    file_positions: FilePositions,
+    toasts: Toasts,
+    offset_base: OffsetBase,
+    theme: egui::ThemePreference,
+    selection_style: SelectionStyle,
+    // When to start the search for what was typed, once typing pauses
+    live_search_due: Option<f64>,
+    // Last title sent to the window, so it is only sent when it changes
+    window_title: String,
 }
 
 impl Default for BinarySearchApp {
     fn default() -> Self {
         Self {
-            selection: None,
-            last_selection: None,
-            file_panel: FilePanel::new(),
+            documents: vec![Document::new()],
+            active: 0,
+            offset_base: OffsetBase::Hexadecimal,
+            theme: egui::ThemePreference::System,
+            selection_style: SelectionStyle::default(),
+            live_search_due: None,
+            window_title: String::new(),
             search_control_panel: SearchControlPanel::new(),
-            search_results_panel: SearchResultsPanel::new(),
-            hex_viewer: HexViewer::new(),
             data_inspector: DataInspector::new(),
-            current_search: CurrentSearch::Empty,
+            bit_view_panel: BitViewPanel::new(),
+            struct_panel: StructPanel::new(),
+            scan_panel: ScanPanel::new(),
+            batch_search_panel: BatchSearchPanel::new(),
+            file_positions: FilePositions::default(),
+            toasts: Toasts::new(),
         }
     }
 }
 
 impl BinarySearchApp {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        Self::default()
+    pub const APP_NAME: &str = "Binary Search Tool";
+    const SEARCH_CONTROLS_KEY: &str = "search_controls";
+    const OFFSET_BASE_KEY: &str = "offset_base";
+    const OPEN_FILES_KEY: &str = "open_files";
+    const LAST_FILE_KEY: &str = "last_file";
+    const THEME_KEY: &str = "theme";
+    const SELECTION_STYLE_KEY: &str = "selection_style";
+    const DATA_INSPECTOR_KEY: &str = "data_inspector";
+    const FILE_POSITIONS_KEY: &str = "file_positions";
+    // Time between frames that collect the results of a running search
+    const SEARCH_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(16);
+    // Seconds after the last change to the search controls before a live search
+    const LIVE_SEARCH_DELAY: f64 = 0.3;
+    // Largest selection "Find selection" also writes into the search controls
+    const SELECTION_INPUT_LIMIT: usize = 4096;
+    // Most integers searched for at once
+    const MAX_SEARCH_VALUES: usize = 64;
+
+    /// Restores the settings and the tabs of the previous run. The file given
+    /// on the command line wins over the ones that were open last time.
+    pub fn new(cc: &eframe::CreationContext<'_>, initial_path: Option<PathBuf>) -> Self {
+        let mut app = Self::default();
+        let mut paths: Vec<PathBuf> = initial_path.iter().cloned().collect();
+        let mut active_path = initial_path;
+        if let Some(storage) = cc.storage {
+            if let Some(controls) = eframe::get_value(storage, Self::SEARCH_CONTROLS_KEY) {
+                app.search_control_panel = controls;
+            }
+            if let Some(base) = eframe::get_value(storage, Self::OFFSET_BASE_KEY) {
+                app.offset_base = base;
+            }
+            if let Some(theme) = eframe::get_value(storage, Self::THEME_KEY) {
+                app.theme = theme;
+            }
+            if let Some(style) = eframe::get_value(storage, Self::SELECTION_STYLE_KEY) {
+                app.selection_style = style;
+            }
+            if let Some(inspector) = eframe::get_value(storage, Self::DATA_INSPECTOR_KEY) {
+                app.data_inspector = inspector;
+            }
+            if let Some(positions) = eframe::get_value(storage, Self::FILE_POSITIONS_KEY) {
+                app.file_positions = positions;
+            }
+            if paths.is_empty() {
+                active_path = eframe::get_value::<PathBuf>(storage, Self::LAST_FILE_KEY);
+                // Runs from before tabs only stored the one file
+                paths = eframe::get_value(storage, Self::OPEN_FILES_KEY)
+                    .unwrap_or_else(|| active_path.iter().cloned().collect());
+                paths.retain(|p| p.exists());
+            }
+        }
+        // `System` follows the OS light/dark setting where eframe can detect it
+        cc.egui_ctx.set_theme(app.theme);
+        let mut documents = Vec::new();
+        for path in paths {
+            let mut doc = Document::new();
+            match doc.file_panel.open_path(path.clone()) {
+                Ok(()) => {
+                    doc.on_file_opened(&mut app.file_positions);
+                    documents.push(doc);
+                }
+                Err(e) => app.show_error(format!("Failed to open {}: {}", path.display(), e)),
+            }
+        }
+        if !documents.is_empty() {
+            app.active = documents
+                .iter()
+                .position(|d| *d.file_panel.get_file_path() == active_path)
+                .unwrap_or(0);
+            app.documents = documents;
+        }
+        app
+    }
+
+    fn show_error(&mut self, message: impl Into<String>) {
+        self.toasts.push(ToastLevel::Error, message);
+    }
+
+    fn doc(&self) -> &Document {
+        &self.documents[self.active]
+    }
+
+    fn doc_mut(&mut self) -> &mut Document {
+        &mut self.documents[self.active]
+    }
+
+    /// Picks a file and opens it in a tab of its own, or in the current tab
+    /// if nothing is loaded there yet.
+    fn open_in_new_tab(&mut self) {
+        let previous = self.active;
+        let added = !self.doc().is_empty();
+        if added {
+            self.documents.push(Document::new());
+            self.active = self.documents.len() - 1;
+        }
+        let doc = &mut self.documents[self.active];
+        let opened = doc.file_panel.open_with_dialog();
+        if opened {
+            doc.on_file_opened(&mut self.file_positions);
+        }
+        if let Some(err) = doc.file_panel.take_error() {
+            self.show_error(err);
+        }
+        if added && !opened {
+            self.documents.pop();
+            self.active = previous;
+        }
+    }
+
+    /// Shows the tab that has `path` open, or opens it in a tab of its own,
+    /// or in the current tab if nothing is loaded there yet.
+    fn open_path_in_tab(&mut self, path: PathBuf) -> bool {
+        if let Some(index) = self
+            .documents
+            .iter()
+            .position(|d| d.file_panel.get_file_path().as_ref() == Some(&path))
+        {
+            self.active = index;
+            return true;
+        }
+        let previous = self.active;
+        let added = !self.doc().is_empty();
+        if added {
+            self.documents.push(Document::new());
+            self.active = self.documents.len() - 1;
+        }
+        let doc = &mut self.documents[self.active];
+        match doc.file_panel.open_path(path.clone()) {
+            Ok(()) => {
+                doc.on_file_opened(&mut self.file_positions);
+                true
+            }
+            Err(e) => {
+                self.show_error(format!("Failed to open {}: {}", path.display(), e));
+                if added {
+                    self.documents.pop();
+                    self.active = previous;
+                }
+                false
+            }
+        }
+    }
+
+    /// Searches every file in `folder` for the value in the search controls.
+    fn start_batch_search(&mut self, folder: PathBuf) {
+        match self.parse_search_input() {
+            Ok(needle) => self.batch_search_panel.start(folder, needle),
+            Err(e) => self.show_error(format!("Failed to parse search input: {}", e)),
+        }
+    }
+
+    /// Opens a file found by the batch search and lists its matches.
+    fn open_batch_result(&mut self, path: PathBuf) {
+        let needle = self.batch_search_panel.get_needle().cloned();
+        if self.open_path_in_tab(path)
+            && let Some(needle) = needle
+        {
+            self.start_search(vec![(String::new(), needle)], false);
+        }
     }
 
-    fn perform_search(&mut self) {
-        // Clear previous results
-        self.search_results_panel.clear_results();
+    /// Closes a tab, cancelling its search, unless it has unsaved edits the
+    /// user wants to keep. Closing the last tab leaves an empty one, so there
+    /// is always a document to show.
+    fn close_tab(&mut self, index: usize) {
+        if !self.documents[index].file_panel.confirm_discard() {
+            return;
+        }
+        let mut doc = self.documents.remove(index);
+        doc.stop_search();
+        doc.remember_position(&mut self.file_positions);
+        if self.documents.is_empty() {
+            self.documents.push(Document::new());
+        }
+        if self.active > index {
+            self.active -= 1;
+        }
+        self.active = self.active.min(self.documents.len() - 1);
+    }
 
-        // Cancel any ongoing search
-        if let CurrentSearch::Searching(_, search) = self.current_search.take() {
-            let _ = search.cancel();
+    /// Saves the listed results to a CSV file picked by the user.
+    fn export_search_results(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .set_file_name("results.csv")
+            .save_file()
+        else {
+            return;
+        };
+        let doc = self.doc();
+        let offset_base = doc.offset_format(self.offset_base);
+        let write = |path: &Path| -> io::Result<()> {
+            let mut out = io::BufWriter::new(std::fs::File::create(path)?);
+            doc.search_results_panel.write_csv(&mut out, offset_base)?;
+            out.flush()
+        };
+        if let Err(e) = write(&path) {
+            self.show_error(format!("Failed to export {}: {}", path.display(), e));
         }
+    }
+
+    /// Names the file in the current tab, and says so while it is searched.
+    fn update_window_title(&mut self, ctx: &egui::Context) {
+        let doc = self.doc();
+        let mut title = format!("{} - {}", doc.title(), Self::APP_NAME);
+        if doc.current_search.is_running() {
+            title.push_str(" (searching…)");
+        }
+        if title != self.window_title {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(title.clone()));
+            self.window_title = title;
+        }
+    }
+
+    fn step_result(&mut self, forward: bool) {
+        self.documents[self.active].step_result(forward, self.offset_base, &mut self.toasts);
+    }
+
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        // Shift+F3 is checked first since consume_key ignores extra Shift
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::SHIFT, egui::Key::F3)) {
+            self.step_result(false);
+        } else if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::F3)) {
+            self.step_result(true);
+        }
+
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::O))
+            && self.doc().file_panel.confirm_discard()
+            && self.doc_mut().file_panel.open_with_dialog()
+        {
+            self.documents[self.active].on_file_opened(&mut self.file_positions);
+        }
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::T)) {
+            self.open_in_new_tab();
+        }
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::W)) {
+            self.close_tab(self.active);
+        }
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::F)) {
+            self.search_control_panel.request_focus();
+        }
+
+        // Text fields keep these for editing their own text
+        if !ctx.wants_keyboard_input() {
+            if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::A)) {
+                let doc = self.doc_mut();
+                let len = doc.file_panel.get_file_data().map_or(0, <[u8]>::len);
+                doc.selection = Selection::whole(len);
+            }
+            if ctx.input_mut(|i| i.consume_key(egui::Modifiers::ALT, egui::Key::ArrowLeft)) {
+                self.doc_mut().go_back();
+            }
+            if ctx.input_mut(|i| i.consume_key(egui::Modifiers::ALT, egui::Key::ArrowRight)) {
+                self.doc_mut().go_forward();
+            }
+        }
+
+        // Text fields and a half-typed hex edit handle Esc themselves
+        if !ctx.wants_keyboard_input()
+            && !self.doc().hex_viewer.has_pending_edit()
+            && ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape))
+        {
+            let doc = &mut self.documents[self.active];
+            if doc.current_search.is_running() {
+                doc.cancel_search(&mut self.toasts);
+            } else {
+                doc.selection = None;
+            }
+        }
+    }
+
+    /// Opens the next snapshot of the data and keeps the results whose value
+    /// passes `filter` against the file searched so far. Work then carries on
+    /// in the new file, so scans can be chained.
+    fn rescan(&mut self, filter: ScanFilter) {
+        let Some(len) = self.doc().current_search.byte_length() else {
+            return;
+        };
+        // Byte runs were not searched for a value to compare against
+        if self.doc().last_search.is_empty() {
+            return;
+        }
+        let needles = match self.parse_search_values() {
+            Ok(values) if values.iter().all(|(_, n)| n.byte_length() == len) => values,
+            Ok(_) => {
+                self.show_error("The search type changed since the last search");
+                return;
+            }
+            Err(e) => {
+                self.show_error(format!("Failed to parse search input: {}", e));
+                return;
+            }
+        };
+        let signed = self.search_control_panel.get_is_signed();
+        let endianness = self.search_control_panel.get_endianness();
+        let doc = &mut self.documents[self.active];
+        let Some(old) = doc.file_panel.get_file_data_arc() else {
+            return;
+        };
+        if !doc.file_panel.confirm_discard() || !doc.file_panel.open_with_dialog() {
+            return;
+        }
+        doc.switch_position_key(&mut self.file_positions);
+
+        let old = old.as_slice();
+        let new = doc.file_panel.get_file_data().unwrap_or_default();
+        let results = doc.search_results_panel.get_search_results();
+        let before = results.len();
+        let kept: Vec<ui::SearchResult> = results
+            .iter()
+            .filter(|r| {
+                let range = r.offset..r.offset + r.len;
+                // Compared with the value this result matched, for a search for several
+                let Some((_, needle)) = needles.get(r.pattern) else {
+                    return false;
+                };
+                match (old.get(range.clone()), new.get(range)) {
+                    (Some(o), Some(n)) => filter.keeps(o, n, needle.as_bytes(), signed, endianness),
+                    _ => false,
+                }
+            })
+            .enumerate()
+            .map(|(index, r)| ui::SearchResult { index, ..*r })
+            .collect();
+
+        self.toasts.push(
+            ToastLevel::Info,
+            format!("Kept {} of {} results", kept.len(), before),
+        );
+        doc.search_results_panel.set_search_results(kept);
+        doc.selection = None;
+        doc.current_result = None;
+    }
+
+    /// Runs the search described by the controls in the current tab. A `live`
+    /// search was started by typing rather than asked for, so it stays quiet
+    /// about input that is not valid yet, such as a lone `0x`, and keeps the
+    /// previous results.
+    fn perform_search(&mut self, live: bool) {
+        self.live_search_due = None;
+        let needles = if self.search_control_panel.get_search_input().is_empty() {
+            Vec::new()
+        } else {
+            match self.parse_search_values() {
+                Ok(needles) => needles,
+                Err(_) if live => return,
+                Err(e) => {
+                    self.show_error(format!("Failed to parse search input: {}", e));
+                    return;
+                }
+            }
+        };
+
+        self.start_search(needles, live);
+    }
+
+    /// Searches the current tab for all of `needles`, each with the value it
+    /// was parsed from, replacing its results. None only clears them, as for
+    /// an empty search value.
+    fn start_search(&mut self, needles: Vec<(String, NeedleOwned)>, live: bool) {
+        // Clear previous results and cancel any ongoing search
+        let doc = &mut self.documents[self.active];
+        doc.search_results_panel.clear_results();
+        doc.current_result = None;
+        doc.stop_search();
+        doc.compare_panel.clear();
+        doc.last_search = needles.clone();
 
         // Get file data
-        let file_data = match self.file_panel.get_file_data_arc() {
+        let file_data = match doc.file_panel.get_file_data_arc() {
             Some(data) => data,
             None => {
-                eprintln!("No file loaded for search");
+                if !live {
+                    self.show_error("No file loaded for search");
+                }
                 return;
             }
         };
 
-        // Get search input
-        let search_input = self.search_control_panel.get_search_input();
-        if search_input.is_empty() {
+        let Some((_, first)) = needles.first() else {
+            return;
+        };
+
+        // Create and start async search
+        let len = first.byte_length();
+        // Only the byte order can make a valid value find nothing: a value
+        // has the same bytes signed or unsigned, or does not parse as one
+        let endianness = self.search_control_panel.get_endianness();
+        doc.endianness_probe = self
+            .search_control_panel
+            .get_search_type()
+            .is_endianness_enabled()
+            .then(|| EndiannessProbe {
+                endianness: endianness.other(),
+                needles: needles.iter().map(|(_, n)| n.reversed()).collect(),
+                announce: !live,
+            });
+        let preview: Vec<String> = needles.iter().map(|(_, n)| n.hex_preview()).collect();
+        let (values, needles): (Vec<String>, Vec<NeedleOwned>) = needles.into_iter().unzip();
+        // Results only name their value when there is more than one
+        let values = if values.len() > 1 { values } else { Vec::new() };
+        doc.search_results_panel
+            .set_searched_for(preview.join(" | "), values);
+        if self.search_control_panel.get_count_only() {
+            let counting = search::count_async(file_data, needles);
+            doc.current_search = CurrentSearch::Counting(0, counting);
+            return;
+        }
+        let search = AsyncSearch::create_multi(file_data, needles, SearchOptions::default());
+        doc.current_search = CurrentSearch::Searching(len, search);
+    }
+
+    /// Searches for the selected bytes as they are. Small selections are also
+    /// put in the search controls, so Next, Skip and rescans use them too.
+    fn find_selection(&mut self) {
+        self.live_search_due = None;
+        let doc = self.doc();
+        let bytes = match (doc.selection, doc.file_panel.get_file_data()) {
+            (Some(sel), Some(data)) => data.get(sel.lower()..=sel.upper()).map(<[u8]>::to_vec),
+            _ => None,
+        };
+        let Some(bytes) = bytes else {
+            self.show_error("Select the bytes to search for first");
             return;
+        };
+        if bytes.len() <= Self::SELECTION_INPUT_LIMIT {
+            self.search_control_panel.set_bytes_input(&bytes);
+        }
+        match NeedleOwned::from_data(bytes) {
+            Ok(needle) => self.start_search(vec![(String::new(), needle)], false),
+            Err(e) => self.show_error(e.to_string()),
         }
+    }
 
-        // Parse search input and create needle
+    /// Selects the first match at or after the selection, `/`-then-`n` style.
+    /// Starts just past the selection when it is the match found last time,
+    /// so repeated presses walk forward through the file.
+    fn find_next_from_cursor(&mut self) {
+        let Some(file_data) = self.doc().file_panel.get_file_data_arc() else {
+            self.show_error("No file loaded for search");
+            return;
+        };
         let needle = match self.parse_search_input() {
             Ok(needle) => needle,
             Err(e) => {
-                eprintln!("Failed to parse search input: {}", e);
+                self.show_error(format!("Failed to parse search input: {}", e));
                 return;
             }
         };
+        let doc = &mut self.documents[self.active];
+        let from = match doc.selection {
+            Some(sel) if doc.active_match == doc.selection => sel.lower() + 1,
+            Some(sel) => sel.lower(),
+            None => 0,
+        };
+        let found = match search::find_next(&file_data, &needle, from) {
+            Some(offset) => Some(offset),
+            None if from > 0 && self.search_control_panel.get_wrap_around() => {
+                let wrapped = search::find_next(&file_data, &needle, 0).filter(|&o| o < from);
+                if wrapped.is_some() {
+                    self.toasts
+                        .push(ToastLevel::Info, "Search wrapped to the start of the file");
+                }
+                wrapped
+            }
+            None => None,
+        };
+        match found {
+            Some(offset) => doc.select_match(
+                offset,
+                needle.byte_length(),
+                self.offset_base,
+                &mut self.toasts,
+            ),
+            None => self.toasts.push(ToastLevel::Info, "No more matches"),
+        }
+    }
 
-        // Create and start async search
-        let len = needle.byte_length();
-        let search = AsyncSearch::create_from_owned(file_data, needle);
-        self.current_search = CurrentSearch::Searching(len, search);
+    /// Jumps past a fill region: the first offset from the selection where
+    /// the search value stops repeating.
+    fn find_first_differing(&mut self) {
+        let Some(file_data) = self.doc().file_panel.get_file_data_arc() else {
+            self.show_error("No file loaded for search");
+            return;
+        };
+        let needle = match self.parse_search_input() {
+            Ok(needle) => needle,
+            Err(e) => {
+                self.show_error(format!("Failed to parse search input: {}", e));
+                return;
+            }
+        };
+        let from = self.doc().selection.map_or(0, |sel| sel.lower());
+        match search::find_first_differing(&file_data, from, &needle) {
+            Some(offset) => self.doc_mut().jump_to(Selection::new(offset)),
+            None => self
+                .toasts
+                .push(ToastLevel::Info, "The value repeats to the end of the file"),
+        }
     }
 
+    /// The one value in the search controls, for the searches that take one.
     fn parse_search_input(&self) -> Result<NeedleOwned, String> {
+        let mut values = self.parse_search_values()?;
+        if values.len() > 1 {
+            return Err("only a single value can be used here".into());
+        }
+        Ok(values.remove(0).1)
+    }
+
+    /// Every value in the search controls, with the text it was parsed from.
+    /// The integer types take several separated by commas or spaces, e.g.
+    /// `100, 200, 0x1F4`; repeated values are only searched for once.
+    fn parse_search_values(&self) -> Result<Vec<(String, NeedleOwned)>, String> {
         let input = self.search_control_panel.get_search_input();
+        let values = if self
+            .search_control_panel
+            .get_search_type()
+            .is_signedness_enabled()
+        {
+            IntParser::split_values(input)
+        } else {
+            Vec::new()
+        };
+        if values.len() > Self::MAX_SEARCH_VALUES {
+            return Err(format!(
+                "at most {} values can be searched for at once",
+                Self::MAX_SEARCH_VALUES
+            ));
+        }
+        if values.len() <= 1 {
+            // Text, one value, or nothing but separators for the parser to reject
+            let input = values.first().copied().unwrap_or(input);
+            return Ok(vec![(input.to_string(), self.parse_search_value(input)?)]);
+        }
+        let mut needles: Vec<(String, NeedleOwned)> = Vec::new();
+        for value in values {
+            let needle = self
+                .parse_search_value(value)
+                .map_err(|e| format!("'{}': {}", value, e))?;
+            if !needles
+                .iter()
+                .any(|(_, n)| n.as_bytes() == needle.as_bytes())
+            {
+                needles.push((value.to_string(), needle));
+            }
+        }
+        Ok(needles)
+    }
+
+    fn parse_search_value(&self, input: &str) -> Result<NeedleOwned, String> {
         let search_type = self.search_control_panel.get_search_type();
         let endianness = self.search_control_panel.get_endianness();
         let is_signed = self.search_control_panel.get_is_signed();
@@ -112,110 +618,75 @@ impl BinarySearchApp {
         let needle = match search_type {
             SearchType::Bit8 => {
                 if is_signed {
-                    let value: i8 = input.parse().map_err(|_| "Invalid signed 8-bit integer")?;
+                    let value = IntParser::parse_i8(input)
+                        .map_err(|e| format!("Invalid signed 8-bit integer: {}", e))?;
                     Needle::I8(value)
                 } else {
-                    let value: u8 = input
-                        .parse()
-                        .map_err(|_| "Invalid unsigned 8-bit integer")?;
+                    let value = IntParser::parse_u8(input)
+                        .map_err(|e| format!("Invalid unsigned 8-bit integer: {}", e))?;
                     Needle::U8(value)
                 }
             }
             SearchType::Bit16 => {
                 if is_signed {
-                    let value: i16 = input.parse().map_err(|_| "Invalid signed 16-bit integer")?;
+                    let value = IntParser::parse_i16(input)
+                        .map_err(|e| format!("Invalid signed 16-bit integer: {}", e))?;
                     Needle::I16(endianness, value)
                 } else {
-                    let value: u16 = input
-                        .parse()
-                        .map_err(|_| "Invalid unsigned 16-bit integer")?;
+                    let value = IntParser::parse_u16(input)
+                        .map_err(|e| format!("Invalid unsigned 16-bit integer: {}", e))?;
                     Needle::U16(endianness, value)
                 }
             }
             SearchType::Bit32 => {
                 if is_signed {
-                    let value: i32 = input.parse().map_err(|_| "Invalid signed 32-bit integer")?;
+                    let value = IntParser::parse_i32(input)
+                        .map_err(|e| format!("Invalid signed 32-bit integer: {}", e))?;
                     Needle::I32(endianness, value)
                 } else {
-                    let value: u32 = input
-                        .parse()
-                        .map_err(|_| "Invalid unsigned 32-bit integer")?;
+                    let value = IntParser::parse_u32(input)
+                        .map_err(|e| format!("Invalid unsigned 32-bit integer: {}", e))?;
                     Needle::U32(endianness, value)
                 }
             }
             SearchType::Bit64 => {
                 if is_signed {
-                    let value: i64 = input.parse().map_err(|_| "Invalid signed 64-bit integer")?;
+                    let value = IntParser::parse_i64(input)
+                        .map_err(|e| format!("Invalid signed 64-bit integer: {}", e))?;
                     Needle::I64(endianness, value)
                 } else {
-                    let value: u64 = input
-                        .parse()
-                        .map_err(|_| "Invalid unsigned 64-bit integer")?;
+                    let value = IntParser::parse_u64(input)
+                        .map_err(|e| format!("Invalid unsigned 64-bit integer: {}", e))?;
                     Needle::U64(endianness, value)
                 }
             }
-            SearchType::String => match encoding {
-                Encoding::UTF8 => Needle::Str(input),
-            },
-            SearchType::Bytes => {
-                // Parse hex string like "41 42 43" or "414243"
-                let cleaned = input.replace(" ", "").replace("0x", "");
-                if cleaned.len() % 2 != 0 {
-                    return Err("Hex string must have even number of characters".to_string());
+            SearchType::String => {
+                let mut bytes = encoding
+                    .encode(input)
+                    .map_err(|c| format!("'{}' cannot be encoded as {}", c, encoding))?;
+                if self.search_control_panel.get_prepend_bom()
+                    && let Some(bom) = encoding.bom()
+                {
+                    bytes.splice(0..0, bom.iter().copied());
                 }
-
-                let mut bytes = Vec::new();
-                for i in (0..cleaned.len()).step_by(2) {
-                    let hex_byte = &cleaned[i..i + 2];
-                    let byte = u8::from_str_radix(hex_byte, 16).map_err(|_| "Invalid hex byte")?;
-                    bytes.push(byte);
-                }
-
-                return Ok(NeedleOwned::from_data(bytes));
+                return NeedleOwned::from_data(bytes).map_err(|e| e.to_string());
             }
-        };
-
-        Ok(needle.into())
-    }
-
-    fn update_search_results(&mut self) {
-        if let CurrentSearch::Searching(_, search) = &self.current_search {
-            let mut results = Vec::new();
-            let mut result_count = 0;
-
-            // Collect up to a reasonable number of results per frame to avoid blocking UI
-            const MAX_RESULTS_PER_FRAME: usize = 100000;
-
-            loop {
-                match search.try_get() {
-                    Ok(offset) => {
-                        results.push(ui::SearchResult {
-                            index: result_count,
-                            offset,
-                        });
-                        result_count += 1;
-
-                        if result_count >= MAX_RESULTS_PER_FRAME {
-                            break;
-                        }
-                    }
-                    Err(SearchState::Pending) => {
-                        // No more results available right now
-                        break;
-                    }
-                    Err(SearchState::Finished) => {
-                        // Search is complete, remove it
-                        self.current_search.finish();
-                        break;
-                    }
-                }
+            SearchType::Bytes => {
+                // Parse hex string like "41 42 43", "0x41, 0x42" or "414243"
+                let bytes = IntParser::parse_hex_bytes(input)
+                    .map_err(|e| format!("Invalid byte string: {}", e))?;
+                return NeedleOwned::from_data(bytes).map_err(|e| e.to_string());
             }
-
-            // Add new results to the panel
-            if !results.is_empty() {
-                self.search_results_panel.add_search_results(results);
+            SearchType::DecBytes => {
+                // Parse decimal list like "72 101 108" or "72, 0x65, 'l'"
+                let bytes = IntParser::parse_dec_bytes(input)
+                    .map_err(|e| format!("Invalid byte list: {}", e))?;
+                return NeedleOwned::from_data(bytes).map_err(|e| e.to_string());
             }
-        }
+        };
+
+        log::debug!("Parsed search input as {}", needle);
+        NeedleOwned::try_from(needle).map_err(|e| e.to_string())
     }
 }
 
@@ -236,6 +707,33 @@ impl BinarySearchApp {
 }
 
 impl eframe::App for BinarySearchApp {
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(
+            storage,
+            Self::SEARCH_CONTROLS_KEY,
+            &self.search_control_panel,
+        );
+        eframe::set_value(storage, Self::OFFSET_BASE_KEY, &self.offset_base);
+        eframe::set_value(storage, Self::THEME_KEY, &self.theme);
+        eframe::set_value(storage, Self::SELECTION_STYLE_KEY, &self.selection_style);
+        eframe::set_value(storage, Self::DATA_INSPECTOR_KEY, &self.data_inspector);
+        for doc in &self.documents {
+            doc.remember_position(&mut self.file_positions);
+        }
+        eframe::set_value(storage, Self::FILE_POSITIONS_KEY, &self.file_positions);
+        let open_files: Vec<&PathBuf> = self
+            .documents
+            .iter()
+            .filter_map(|d| d.file_panel.get_file_path().as_ref())
+            .collect();
+        eframe::set_value(storage, Self::OPEN_FILES_KEY, &open_files);
+        eframe::set_value(
+            storage,
+            Self::LAST_FILE_KEY,
+            self.doc().file_panel.get_file_path(),
+        );
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         ctx.send_viewport_cmd(egui::ViewportCommand::MinInnerSize(egui::vec2(
             Self::APP_MIN_WIDTH,
@@ -252,9 +750,103 @@ impl eframe::App for BinarySearchApp {
         // });
         // Left-right split layout
 
-        // Check for new search results
-        self.update_search_results();
-        self.last_selection = self.selection;
+        // Check for truncated files and new search results, in background tabs too
+        let max_results = self.search_control_panel.get_max_results();
+        for doc in &mut self.documents {
+            if doc.file_panel.check_truncated() {
+                doc.cancel_search(&mut self.toasts);
+            }
+            if let Some(err) = doc.file_panel.take_error() {
+                self.toasts.push(ToastLevel::Error, err);
+            }
+            doc.drop_stale_comparison(&mut self.toasts);
+            doc.update_search_results(max_results, &mut self.toasts);
+            doc.strings_panel.update();
+            if let Some(err) = doc.strings_panel.take_error() {
+                self.toasts.push(ToastLevel::Error, err);
+            }
+        }
+        self.batch_search_panel.update();
+        if let Some(err) = self.batch_search_panel.take_error() {
+            self.show_error(err);
+        }
+        self.update_window_title(ctx);
+
+        self.handle_shortcuts(ctx);
+        egui::TopBottomPanel::top("BinarySearchApp.Toolbar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(
+                        self.doc().history.can_go_back(),
+                        egui::Button::new("◀ Back"),
+                    )
+                    .on_hover_text("Alt+Left")
+                    .clicked()
+                {
+                    self.doc_mut().go_back();
+                }
+                if ui
+                    .add_enabled(
+                        self.doc().history.can_go_forward(),
+                        egui::Button::new("Forward ▶"),
+                    )
+                    .on_hover_text("Alt+Right")
+                    .clicked()
+                {
+                    self.doc_mut().go_forward();
+                }
+
+                ui.separator();
+
+                ui.label("Offsets:");
+                for base in OffsetBase::iter() {
+                    ui.radio_value(&mut self.offset_base, base, format!("{}", base));
+                }
+
+                ui.separator();
+
+                ui.label("Theme:");
+                let previous = self.theme;
+                ui.radio_value(&mut self.theme, egui::ThemePreference::System, "System");
+                ui.radio_value(&mut self.theme, egui::ThemePreference::Dark, "Dark");
+                ui.radio_value(&mut self.theme, egui::ThemePreference::Light, "Light");
+                if self.theme != previous {
+                    ctx.set_theme(self.theme);
+                }
+                ui.menu_button("Selection", |ui| self.selection_style.render(ui))
+                    .response
+                    .on_hover_text("Color and opacity of the hex viewer selection");
+            });
+        });
+        egui::TopBottomPanel::top("BinarySearchApp.Tabs").show(ctx, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                let mut close = None;
+                for (i, doc) in self.documents.iter().enumerate() {
+                    let mut tab = ui.selectable_label(i == self.active, doc.title());
+                    if let Some(path) = doc.file_panel.get_file_path() {
+                        tab = tab.on_hover_text(path.display().to_string());
+                    }
+                    if tab.clicked() {
+                        self.active = i;
+                    }
+                    if tab.middle_clicked()
+                        || ui.small_button("×").on_hover_text("Close tab").clicked()
+                    {
+                        close = Some(i);
+                    }
+                }
+                if let Some(i) = close {
+                    self.close_tab(i);
+                }
+                if ui
+                    .button("+")
+                    .on_hover_text("Open in a new tab (Ctrl+T)")
+                    .clicked()
+                {
+                    self.open_in_new_tab();
+                }
+            });
+        });
         egui::CentralPanel::default().show(ctx, |ui| {
             let sb: StripBuilder<'_> = StripBuilder::new(ui)
                 .size(Size::relative(Self::CELL0_RATIO))
@@ -264,55 +856,240 @@ impl eframe::App for BinarySearchApp {
                 strip.cell(|ui| {
                     // Left panel - File controls, Search controls, Search results
                     // File panel
-                    if self.file_panel.render(ui) {
-                        // File was opened, clear search results and cancel ongoing search
-                        self.selection = None;
-                        self.search_results_panel.clear_results();
-                        if let CurrentSearch::Searching(_, search) = self.current_search.take() {
-                            let _ = search.cancel();
+                    let doc = &mut self.documents[self.active];
+                    let mut search_again = None;
+                    if doc.file_panel.render(ui) {
+                        // The needles already hold the type, byte order and
+                        // encoding they were searched with
+                        let last_search = std::mem::take(&mut doc.last_search);
+                        if doc.file_panel.take_reloaded()
+                            && self.search_control_panel.get_search_on_reload()
+                            && !last_search.is_empty()
+                        {
+                            search_again = Some(last_search);
                         }
+                        doc.on_file_opened(&mut self.file_positions);
+                    }
+                    if let Some(err) = doc.file_panel.take_error() {
+                        self.show_error(err);
+                    }
+                    if let Some(needles) = search_again {
+                        self.start_search(needles, false);
                     }
 
                     ui.separator();
 
                     // Search controls panel
-                    if self.search_control_panel.render(ui) {
-                        self.perform_search();
+                    let has_selection = self.doc().selection.is_some();
+                    match self.search_control_panel.render(ui, has_selection) {
+                        Some(SearchRequest::All) => self.perform_search(false),
+                        Some(SearchRequest::NextFromCursor) => self.find_next_from_cursor(),
+                        Some(SearchRequest::FirstDiffering) => self.find_first_differing(),
+                        Some(SearchRequest::Selection) => self.find_selection(),
+                        None => {}
+                    }
+                    if self.search_control_panel.take_input_changed() {
+                        let now = ui.input(|i| i.time);
+                        self.live_search_due = Some(now + Self::LIVE_SEARCH_DELAY);
+                    }
+                    if let Some(due) = self.live_search_due {
+                        let remaining = due - ui.input(|i| i.time);
+                        if remaining <= 0. {
+                            self.perform_search(true);
+                        } else {
+                            ui.ctx()
+                                .request_repaint_after(std::time::Duration::from_secs_f64(
+                                    remaining,
+                                ));
+                        }
                     }
 
                     ui.separator();
 
-                    // Search results panel
-                    if let Some(sel) = self.search_results_panel.render(ui) {
-                        match self.current_search {
-                            CurrentSearch::Empty => todo!(),
-                            CurrentSearch::Searching(len, _) => {
-                                self.selection = Some(Selection::range(sel, sel + len - 1));
+                    // Result navigation
+                    let result_count = self.doc().search_results_panel.get_search_results().len();
+                    ui.horizontal(|ui| {
+                        ui.add_enabled_ui(result_count > 0, |ui| {
+                            if ui.button("Prev").on_hover_text("Shift+F3").clicked() {
+                                self.step_result(false);
                             }
-                            CurrentSearch::Finished(len) => {
-                                self.selection = Some(Selection::range(sel, sel + len - 1));
+                            if ui.button("Next").on_hover_text("F3").clicked() {
+                                self.step_result(true);
                             }
+                        });
+                        if let Some(i) = self.doc().current_result {
+                            ui.label(format!("Result {} of {}", i + 1, result_count));
                         }
+                    });
+
+                    let can_rescan = self.doc().current_search.is_complete()
+                        && !self.doc().last_search.is_empty()
+                        && result_count > 0;
+                    if let Some(filter) = self.scan_panel.render(ui, can_rescan) {
+                        self.rescan(filter);
+                    }
+                    if let Some(err) = self.doc_mut().file_panel.take_error() {
+                        self.show_error(err);
+                    }
+
+                    // Search results panel
+                    let doc = &mut self.documents[self.active];
+                    match doc.search_results_panel.render(
+                        ui,
+                        doc.offset_format(self.offset_base),
+                        doc.current_search.status(),
+                        doc.file_panel.get_file_data(),
+                        doc.file_panel.get_parts(),
+                    ) {
+                        Some(ResultsAction::Goto(index)) => {
+                            doc.goto_result(index, self.offset_base, &mut self.toasts)
+                        }
+                        Some(ResultsAction::Export) => self.export_search_results(),
+                        Some(ResultsAction::Clear) => doc.clear_search(),
+                        Some(ResultsAction::SwitchEndianness(endianness)) => {
+                            self.search_control_panel.set_endianness(endianness);
+                            self.perform_search(false);
+                        }
+                        None => {}
                     }
                 });
                 strip.cell(|ui| {
-                    let diff = self.selection != self.last_selection;
+                    let doc = &mut self.documents[self.active];
+                    let diff = doc.selection != doc.last_selection;
+                    let results = match doc.current_search.byte_length() {
+                        Some(_) => doc.search_results_panel.get_search_results().as_slice(),
+                        None => &[][..],
+                    };
+                    let (matches, diffs) = if doc.compare_panel.is_highlighted() {
+                        (&[][..], results)
+                    } else {
+                        (results, &[][..])
+                    };
+                    let highlights = Highlights {
+                        matches,
+                        bookmarks: doc.bookmarks_panel.get_bookmarks(),
+                        edited: doc.file_panel.get_edited_offsets(),
+                        selection_is_match: doc.active_match.is_some()
+                            && doc.active_match == doc.selection,
+                        diffs,
+                        selection_style: self.selection_style,
+                        inspector_anchor: doc.inspected_offset(),
+                    };
 
-                    self.hex_viewer.render(
+                    let offset_format = doc.offset_format(self.offset_base);
+                    let edit = doc.hex_viewer.render(
                         ui,
-                        self.file_panel.get_file_data(),
-                        &mut self.selection,
+                        doc.file_panel.get_file_data(),
+                        &mut doc.selection,
                         diff,
+                        highlights,
+                        offset_format,
                     );
+                    // Selection changes made by the viewer itself never scroll it;
+                    // anything changed after this point scrolls on the next frame
+                    doc.last_selection = doc.selection;
+                    if let Some(edit) = edit {
+                        doc.file_panel.write_byte(edit.offset, edit.value);
+                    }
+                    if let Some(err) = doc.hex_viewer.take_error() {
+                        self.show_error(err);
+                    }
                 });
                 strip.cell(|ui| {
-                    self.data_inspector.render(
+                    let doc = &mut self.documents[self.active];
+                    let offset_format = doc.offset_format(self.offset_base);
+                    let data = doc.file_panel.get_file_data().unwrap_or_default();
+                    let selected_offset = doc.inspected_offset();
+                    if let Some(offset) = self.data_inspector.render(
                         ui,
-                        self.selection.map(|s| s.lower()),
-                        self.file_panel.get_file_data(),
+                        selected_offset,
+                        selected_offset.and_then(|off| DataInspector::window(data, off)),
+                        data.len(),
+                        offset_format,
+                        &mut doc.relative_origin,
+                    ) {
+                        doc.jump_to(Selection::new(offset));
+                        ui.ctx().request_repaint();
+                    }
+
+                    self.bit_view_panel
+                        .render(ui, doc.selection, doc.file_panel.get_file_data());
+
+                    if let Some(offset) =
+                        doc.bookmarks_panel.render(ui, doc.selection, offset_format)
+                    {
+                        doc.jump_to(Selection::new(offset));
+                        ui.ctx().request_repaint();
+                    }
+
+                    doc.checksum_panel.render(
+                        ui,
+                        doc.selection,
+                        doc.file_panel.get_file_data_arc(),
+                        doc.file_panel.get_revision(),
                     );
+
+                    doc.histogram_panel.render(
+                        ui,
+                        doc.selection,
+                        doc.file_panel.get_file_data_arc(),
+                        doc.file_panel.get_revision(),
+                    );
+
+                    if let Some(path) =
+                        doc.compare_panel.render(ui, doc.file_panel.get_file_data().is_some())
+                        && let Err(e) = doc.compare_with(path.clone())
+                    {
+                        self.toasts.push(
+                            ToastLevel::Error,
+                            format!("Failed to compare with {}: {}", path.display(), e),
+                        );
+                    }
+
+                    if let Some((offset, len)) = doc.strings_panel.render(
+                        ui,
+                        doc.file_panel.get_file_data_arc(),
+                        offset_format,
+                    ) {
+                        doc.select_match(offset, len, self.offset_base, &mut self.toasts);
+                        ui.ctx().request_repaint();
+                    }
+
+                    if let Some((byte, min_len)) =
+                        doc.runs_panel.render(ui, doc.file_panel.get_file_data().is_some())
+                    {
+                        doc.find_runs(byte, min_len);
+                    }
+
+                    self.struct_panel.render(
+                        ui,
+                        doc.selection.map(|s| s.lower()),
+                        doc.file_panel.get_file_data(),
+                        offset_format,
+                    );
+
+                    let can_search = !self.search_control_panel.get_search_input().is_empty();
+                    match self.batch_search_panel.render(ui, can_search) {
+                        Some(BatchAction::Search(folder)) => self.start_batch_search(folder),
+                        Some(BatchAction::Open(path)) => self.open_batch_result(path),
+                        None => {}
+                    }
                 })
             });
         });
+
+        self.toasts.render(ctx);
+
+        // egui only runs a frame on input or when asked to, and results are
+        // only collected in a frame. Ask for the next one while any tab's
+        // search or string scan streams, including one started partway through this frame
+        if self
+            .documents
+            .iter()
+            .any(|doc| doc.is_streaming() || doc.strings_panel.is_running())
+            || self.batch_search_panel.is_running()
+        {
+            ctx.request_repaint_after(Self::SEARCH_POLL_INTERVAL);
+        }
     }
 }
@@ -0,0 +1,300 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::search::{self, AsyncStream, NeedleOwned, SearchState};
+use crate::ui::components::FileData;
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+
+/// What the user asked the batch search panel to do.
+pub enum BatchAction {
+    /// Search every file in the folder for the value in the search controls.
+    Search(PathBuf),
+    /// Open a file that has matches.
+    Open(PathBuf),
+}
+
+// What the worker streams: the matches in one chunk of the file it is
+// searching, then the file once it is done, or why it could not be read
+enum Scanned {
+    Found(usize),
+    File(PathBuf, io::Result<()>),
+}
+
+/// Searches every file in a folder for one needle, a file at a time on a
+/// worker thread, and lists the files that contain it with their match counts.
+pub struct BatchSearchPanel {
+    folder: Option<PathBuf>,
+    needle: Option<NeedleOwned>,
+    scan: Option<AsyncStream<Scanned>>,
+    // Files the worker has not reported yet
+    left: usize,
+    // Matches in the file being searched, so far
+    counted: usize,
+    // Files with at least one match, in the order they were searched
+    hits: Vec<(PathBuf, usize)>,
+    searched: usize,
+    // Files that could not be searched, with the reason
+    skipped: Vec<(PathBuf, String)>,
+    // Last failure, until the app picks it up with `take_error`
+    error: Option<String>,
+}
+
+impl Default for BatchSearchPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BatchSearchPanel {
+    pub fn new() -> Self {
+        Self {
+            folder: None,
+            needle: None,
+            scan: None,
+            left: 0,
+            counted: 0,
+            hits: Vec::new(),
+            searched: 0,
+            skipped: Vec::new(),
+            error: None,
+        }
+    }
+
+    pub fn take_error(&mut self) -> Option<String> {
+        self.error.take()
+    }
+
+    /// The needle of the last batch search, to search an opened file with.
+    pub fn get_needle(&self) -> Option<&NeedleOwned> {
+        self.needle.as_ref()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.scan.is_some()
+    }
+
+    /// Regular files directly in `folder`, by name. Directories and symlinks
+    /// are left out, so the search stays in the folder and cannot loop.
+    fn list_files(folder: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(folder)? {
+            let entry = entry?;
+            // Unlike `Path::is_file`, this does not follow symlinks
+            if entry.file_type()?.is_file() {
+                files.push(entry.path());
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    /// Replaces the listed files with a search of `folder` for `needle`.
+    pub fn start(&mut self, folder: PathBuf, needle: NeedleOwned) {
+        self.stop();
+        self.hits.clear();
+        self.skipped.clear();
+        self.searched = 0;
+        match Self::list_files(&folder) {
+            Ok(files) => {
+                self.left = files.len();
+                let needle = needle.clone();
+                // Files are loaded as the file panel loads them, so small
+                // ones are copied and cannot fault if they are truncated
+                self.scan = Some(AsyncStream::spawn(64, move |tx| {
+                    for path in files {
+                        let result = match FileData::open(&path) {
+                            Ok(data) => {
+                                for found in search::count_in_chunks(data.as_slice(), &needle) {
+                                    if tx.send(Scanned::Found(found)).is_err() {
+                                        return;
+                                    }
+                                }
+                                Ok(())
+                            }
+                            Err(e) => Err(e),
+                        };
+                        if tx.send(Scanned::File(path, result)).is_err() {
+                            return;
+                        }
+                    }
+                }));
+            }
+            Err(e) => self.error = Some(format!("Failed to read {}: {}", folder.display(), e)),
+        }
+        self.folder = Some(folder);
+        self.needle = Some(needle);
+    }
+
+    /// Cancels the search, keeping the files found so far.
+    pub fn stop(&mut self) {
+        self.left = 0;
+        self.counted = 0;
+        if let Some(scan) = self.scan.take()
+            && let Err(e) = scan.cancel()
+        {
+            log::warn!("Search thread failed: {}", e);
+        }
+    }
+
+    /// Lists the files the worker has counted since the last frame.
+    pub fn update(&mut self) {
+        let Some(scan) = &self.scan else {
+            return;
+        };
+        let state = scan.drain(|scanned| match scanned {
+            Scanned::Found(found) => self.counted += found,
+            Scanned::File(path, result) => {
+                self.left -= 1;
+                let count = std::mem::take(&mut self.counted);
+                match result {
+                    Ok(()) => {
+                        self.searched += 1;
+                        if count > 0 {
+                            self.hits.push((path, count));
+                        }
+                    }
+                    Err(e) => self.skipped.push((path, e.to_string())),
+                }
+            }
+        });
+        match state {
+            SearchState::Pending => {}
+            SearchState::Finished => self.stop(),
+            SearchState::Panicked => {
+                self.stop();
+                self.error = Some("Batch search failed unexpectedly".into());
+            }
+        }
+    }
+
+    /// `can_search` is whether the search controls hold a value to search for.
+    pub fn render(&mut self, ui: &mut egui::Ui, can_search: bool) -> Option<BatchAction> {
+        let mut action = None;
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Batch Search");
+                    if ui
+                        .add_enabled(can_search, egui::Button::new("Folder..."))
+                        .on_hover_text(
+                            "Search every file in a folder for the value in the search controls",
+                        )
+                        .clicked()
+                        && let Some(folder) = rfd::FileDialog::new().pick_folder()
+                    {
+                        action = Some(BatchAction::Search(folder));
+                    }
+                    if self.is_running() {
+                        if ui.button("Stop").clicked() {
+                            self.stop();
+                        }
+                        ui.spinner();
+                    }
+                });
+
+                let Some(folder) = &self.folder else {
+                    ui.label("No folder searched");
+                    return;
+                };
+                let name = folder
+                    .file_name()
+                    .unwrap_or(folder.as_os_str())
+                    .to_string_lossy();
+                let left = self.left;
+                let mut status = format!(
+                    "{} of {} files in {} match",
+                    self.hits.len(),
+                    self.searched,
+                    name
+                );
+                if left > 0 {
+                    status.push_str(&format!(", {} left", left));
+                }
+                ui.label(status);
+                if !self.skipped.is_empty() {
+                    let reasons: Vec<String> = self
+                        .skipped
+                        .iter()
+                        .map(|(path, reason)| format!("{}: {}", path.display(), reason))
+                        .collect();
+                    ui.label(format!("{} files could not be read", self.skipped.len()))
+                        .on_hover_text(reasons.join("\n"));
+                }
+
+                TableBuilder::new(ui)
+                    .id_salt("BatchSearchPanel.Table")
+                    .striped(true)
+                    .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                    .column(Column::remainder().clip(true)) // File column
+                    .column(Column::exact(70.)) // Matches column
+                    .column(Column::exact(40.)) // Action column
+                    .max_scroll_height(160.)
+                    .header(20.0, |mut header| {
+                        header.col(|ui| {
+                            ui.strong("File");
+                        });
+                        header.col(|ui| {
+                            ui.strong("Matches");
+                        });
+                        header.col(|ui| {
+                            ui.strong("Action");
+                        });
+                    })
+                    .body(|body| {
+                        body.rows(18.0, self.hits.len(), |mut row| {
+                            let (path, count) = &self.hits[row.index()];
+                            row.col(|ui| {
+                                let name = path.file_name().unwrap_or(path.as_os_str());
+                                ui.label(name.to_string_lossy())
+                                    .on_hover_text(path.display().to_string());
+                            });
+                            row.col(|ui| {
+                                ui.monospace(format!("{}", count));
+                            });
+                            row.col(|ui| {
+                                if ui.small_button("Open").clicked() {
+                                    action = Some(BatchAction::Open(path.clone()));
+                                }
+                            });
+                        });
+                    });
+            });
+        });
+
+        action
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempPath;
+
+    #[test]
+    fn test_list_files() {
+        let dir = TempPath::new("batch");
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("b.bin"), b"xx").unwrap();
+        fs::write(dir.join("a.bin"), b"").unwrap();
+        fs::write(dir.join("sub").join("c.bin"), b"xx").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(dir.join("b.bin"), dir.join("link.bin")).unwrap();
+
+        let files = BatchSearchPanel::list_files(&dir).unwrap();
+        assert_eq!(files, [dir.join("a.bin"), dir.join("b.bin")]);
+
+        let mut panel = BatchSearchPanel::new();
+        let needle = NeedleOwned::try_from(crate::search::Needle::Str("x")).unwrap();
+        panel.start(dir.to_path_buf(), needle);
+        while panel.is_running() {
+            panel.update();
+            std::thread::yield_now();
+        }
+        assert_eq!(panel.hits, [(dir.join("b.bin"), 2)]);
+        assert_eq!(panel.searched, 2);
+        assert!(panel.skipped.is_empty());
+    }
+}
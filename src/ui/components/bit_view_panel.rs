@@ -0,0 +1,138 @@
+use crate::search::Endianness;
+use crate::ui::util::Selection;
+use eframe::egui;
+
+/// Lays out the bits of the selected byte, or of the selection read as one
+/// integer, as cells labelled with their index. Easier to read than a binary
+/// number for flag fields.
+pub struct BitViewPanel {
+    msb_first: bool,
+    // Read up to `MAX_BYTES` of the selection as one integer instead of its first byte
+    whole_selection: bool,
+    endianness: Endianness,
+}
+
+impl Default for BitViewPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BitViewPanel {
+    const MAX_BYTES: usize = 8;
+    const BITS_PER_LINE: usize = 8;
+
+    pub fn new() -> Self {
+        Self {
+            msb_first: true,
+            whole_selection: false,
+            endianness: Endianness::LittleEndian,
+        }
+    }
+
+    /// The value shown and its width in bits, `None` if the selected bytes
+    /// are not all in the data.
+    fn value(&self, selection: Selection, data: &[u8]) -> Option<(u64, usize)> {
+        let count = if self.whole_selection {
+            selection.byte_count().min(Self::MAX_BYTES)
+        } else {
+            1
+        };
+        let bytes = data.get(selection.lower()..)?.get(..count)?;
+        // `read_int` only fails for widths outside 1..=8 bytes
+        let value = self.endianness.read_int(bytes, false)? as u64;
+        Some((value, count * 8))
+    }
+
+    /// Bit indices in display order, most significant first if `msb_first`.
+    fn bit_order(width: usize, msb_first: bool) -> Vec<usize> {
+        if msb_first {
+            (0..width).rev().collect()
+        } else {
+            (0..width).collect()
+        }
+    }
+
+    fn paint_bits(ui: &mut egui::Ui, value: u64, width: usize, msb_first: bool) {
+        let order = Self::bit_order(width, msb_first);
+        for (line, bits) in order.chunks(Self::BITS_PER_LINE).enumerate() {
+            egui::Grid::new(("BitViewPanel.Line", line))
+                .spacing(egui::vec2(6.0, 0.0))
+                .show(ui, |ui| {
+                    for &bit in bits {
+                        ui.label(egui::RichText::new(bit.to_string()).small().weak());
+                    }
+                    ui.end_row();
+                    for &bit in bits {
+                        let set = (value >> bit) & 1 == 1;
+                        let text = egui::RichText::new(if set { "1" } else { "0" }).monospace();
+                        ui.label(if set { text.strong() } else { text.weak() })
+                            .on_hover_text(format!("Bit {}, mask 0x{:X}", bit, 1u64 << bit));
+                    }
+                    ui.end_row();
+                });
+        }
+    }
+
+    pub fn render(
+        &mut self,
+        ui: &mut egui::Ui,
+        selection: Option<Selection>,
+        file_data: Option<&[u8]>,
+    ) {
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Bit View");
+                    ui.checkbox(&mut self.msb_first, "MSB first");
+                    ui.checkbox(&mut self.whole_selection, "Whole selection")
+                        .on_hover_text("Read up to 8 selected bytes as one integer");
+                    ui.add_enabled_ui(self.whole_selection, |ui| {
+                        ui.radio_value(&mut self.endianness, Endianness::LittleEndian, "LE");
+                        ui.radio_value(&mut self.endianness, Endianness::BigEndian, "BE");
+                    });
+                });
+
+                let value = match (selection, file_data) {
+                    (Some(sel), Some(data)) => self.value(sel, data),
+                    _ => None,
+                };
+                let Some((value, width)) = value else {
+                    ui.label("No selection");
+                    return;
+                };
+                ui.label(format!("0x{:0w$X} ({})", value, value, w = width / 4));
+                Self::paint_bits(ui, value, width, self.msb_first);
+            });
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value() {
+        let data = [0x01, 0x80, 0xFF];
+        let mut panel = BitViewPanel::new();
+        assert_eq!(panel.value(Selection::range(0, 1), &data), Some((0x01, 8)));
+        assert_eq!(panel.value(Selection::new(3), &data), None);
+
+        panel.whole_selection = true;
+        assert_eq!(
+            panel.value(Selection::range(0, 1), &data),
+            Some((0x8001, 16))
+        );
+        panel.endianness = Endianness::BigEndian;
+        assert_eq!(
+            panel.value(Selection::range(0, 1), &data),
+            Some((0x0180, 16))
+        );
+        // A selection running past the end has no value
+        assert_eq!(panel.value(Selection::range(2, 3), &data), None);
+
+        assert_eq!(BitViewPanel::bit_order(8, true), [7, 6, 5, 4, 3, 2, 1, 0]);
+        assert_eq!(BitViewPanel::bit_order(8, false), [0, 1, 2, 3, 4, 5, 6, 7]);
+    }
+}
@@ -0,0 +1,122 @@
+use crate::ui::util::{Bookmark, OffsetFormat, Selection, copy_offset};
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+
+pub struct BookmarksPanel {
+    bookmarks: Vec<Bookmark>,
+    new_name: String,
+}
+
+impl Default for BookmarksPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BookmarksPanel {
+    pub fn new() -> Self {
+        Self {
+            bookmarks: Vec::new(),
+            new_name: String::new(),
+        }
+    }
+
+    pub fn get_bookmarks(&self) -> &Vec<Bookmark> {
+        &self.bookmarks
+    }
+
+    pub fn clear_bookmarks(&mut self) {
+        self.bookmarks.clear();
+    }
+
+    /// Returns the offset of the bookmark the user asked to jump to, if any.
+    pub fn render(
+        &mut self,
+        ui: &mut egui::Ui,
+        selection: Option<Selection>,
+        offset_base: OffsetFormat,
+    ) -> Option<usize> {
+        let mut selected_offset = None;
+        let mut removed = None;
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Bookmarks");
+
+                ui.horizontal(|ui| {
+                    let add_enabled = selection.is_some();
+                    if ui
+                        .add_enabled(add_enabled, egui::Button::new("Add bookmark"))
+                        .clicked()
+                        && let Some(sel) = selection
+                    {
+                        let name = match self.new_name.trim() {
+                            "" => format!("Bookmark {}", self.bookmarks.len() + 1),
+                            name => name.to_string(),
+                        };
+                        self.bookmarks.push(Bookmark {
+                            name,
+                            offset: sel.lower(),
+                        });
+                        self.new_name.clear();
+                    }
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.new_name)
+                            .hint_text("Name")
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+
+                TableBuilder::new(ui)
+                    .id_salt("BookmarksPanel.Table")
+                    .striped(true)
+                    .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                    .column(Column::remainder()) // Name column
+                    .column(Column::exact(90.)) // Offset column
+                    .column(Column::exact(50.)) // Action column
+                    .max_scroll_height(160.)
+                    .header(20.0, |mut header| {
+                        header.col(|ui| {
+                            ui.strong("Name");
+                        });
+                        header.col(|ui| {
+                            ui.strong("Offset");
+                        });
+                        header.col(|ui| {
+                            ui.strong("Action");
+                        });
+                    })
+                    .body(|body| {
+                        body.rows(18.0, self.bookmarks.len(), |mut row| {
+                            let row_index = row.index();
+                            let bookmark = &self.bookmarks[row_index];
+
+                            row.col(|ui| {
+                                ui.label(&bookmark.name);
+                            });
+                            row.col(|ui| {
+                                ui.label(
+                                    egui::RichText::new(offset_base.format(bookmark.offset))
+                                        .text_style(egui::TextStyle::Monospace),
+                                )
+                                .context_menu(|ui| copy_offset(ui, bookmark.offset, offset_base));
+                            });
+                            row.col(|ui| {
+                                if ui.small_button("Go").clicked() {
+                                    selected_offset = Some(bookmark.offset);
+                                }
+                                if ui.small_button("X").clicked() {
+                                    removed = Some(row_index);
+                                }
+                            });
+                        });
+                    });
+            });
+        });
+
+        if let Some(index) = removed {
+            self.bookmarks.remove(index);
+        }
+        selected_offset
+    }
+}
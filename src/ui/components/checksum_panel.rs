@@ -0,0 +1,148 @@
+use crate::checksum::Checksums;
+use crate::search::{AsyncStream, SearchState};
+use crate::ui::components::FileData;
+use crate::ui::util::Selection;
+use eframe::egui;
+
+// Selection and data revision checksums belong to
+type Key = (Selection, u64);
+
+// Checksums of a selection the worker is still going through
+struct Summing {
+    key: Key,
+    stream: AsyncStream<Checksums>,
+    sums: Checksums,
+}
+
+/// Sums `selection` on a worker thread that sends the checksums so far after
+/// each chunk, the last of them covering the whole selection.
+fn sum_selection(data: FileData, selection: Selection) -> AsyncStream<Checksums> {
+    // Small enough to cancel promptly while a selection is dragged
+    const CHUNK: usize = 1 << 20;
+    AsyncStream::spawn(4, move |tx| {
+        let data = data.as_slice();
+        let upper = selection.upper().min(data.len() - 1);
+        let mut sums = Checksums::new();
+        for chunk in data[selection.lower().min(upper)..=upper].chunks(CHUNK) {
+            sums.update(chunk);
+            if tx.send(sums).is_err() {
+                return;
+            }
+        }
+    })
+}
+
+pub struct ChecksumPanel {
+    // Checksums of the last selection summed, `None` if its worker failed
+    cached: Option<(Key, Option<Checksums>)>,
+    summing: Option<Summing>,
+}
+
+impl Default for ChecksumPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChecksumPanel {
+    pub fn new() -> Self {
+        Self {
+            cached: None,
+            summing: None,
+        }
+    }
+
+    fn stop(&mut self) {
+        if let Some(summing) = self.summing.take()
+            && let Err(e) = summing.stream.cancel()
+        {
+            log::warn!("Checksum thread failed: {}", e);
+        }
+    }
+
+    /// Starts summing `key`'s selection unless it is cached or already being
+    /// summed, cancelling any other sum, and collects the worker's progress.
+    fn update(&mut self, key: Key, data: &FileData) {
+        if self.cached.as_ref().is_some_and(|(k, _)| *k == key) {
+            self.stop();
+            return;
+        }
+        if self.summing.as_ref().is_none_or(|s| s.key != key) {
+            self.stop();
+            self.summing = Some(Summing {
+                key,
+                stream: sum_selection(data.clone(), key.0),
+                sums: Checksums::new(),
+            });
+        }
+        let Summing { stream, sums, .. } = self.summing.as_mut().unwrap();
+        match stream.drain(|latest| *sums = latest) {
+            SearchState::Pending => {}
+            SearchState::Finished => {
+                let sums = *sums;
+                self.stop();
+                self.cached = Some((key, Some(sums)));
+            }
+            SearchState::Panicked => {
+                self.stop();
+                self.cached = Some((key, None));
+            }
+        }
+    }
+
+    pub fn render(
+        &mut self,
+        ui: &mut egui::Ui,
+        selection: Option<Selection>,
+        file_data: Option<FileData>,
+        revision: u64,
+    ) {
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Selection Checksums");
+
+                let (Some(sel), Some(data)) =
+                    (selection, file_data.filter(|d| !d.as_slice().is_empty()))
+                else {
+                    self.stop();
+                    ui.label("No selection");
+                    return;
+                };
+                let key = (sel, revision);
+                self.update(key, &data);
+                let sums = match &self.cached {
+                    Some((k, Some(sums))) if *k == key => sums,
+                    Some((k, None)) if *k == key => {
+                        ui.label("Failed to compute checksums");
+                        return;
+                    }
+                    // The spinner asks for the frames that collect the sums
+                    _ => {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label("Computing checksums...");
+                        });
+                        return;
+                    }
+                };
+                egui::Grid::new("ChecksumPanel.Grid")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (name, value) in [
+                            ("CRC32", format!("{:08X}", sums.crc32())),
+                            ("CRC32C", format!("{:08X}", sums.crc32c())),
+                            ("Adler-32", format!("{:08X}", sums.adler32())),
+                            ("Sum32", format!("{:08X}", sums.sum32())),
+                            ("Sum8", format!("{:02X}", sums.sum32() as u8)),
+                            ("XOR8", format!("{:02X}", sums.xor8())),
+                        ] {
+                            ui.label(name);
+                            ui.monospace(value);
+                            ui.end_row();
+                        }
+                    });
+            });
+        });
+    }
+}
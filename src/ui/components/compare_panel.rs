@@ -0,0 +1,127 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use eframe::egui;
+
+/// The file compared with and what both sides looked like at the time, to
+/// tell when the listed differences no longer hold.
+struct Compared {
+    path: PathBuf,
+    // Length and modification time of the other file
+    stamp: Option<(u64, SystemTime)>,
+    // When `stamp` was last compared with the file on disk
+    checked: Instant,
+    // File panel revision of the open file
+    revision: u64,
+}
+
+/// Compares the open file with another one. The differing ranges are found
+/// on the search worker and listed as search results, so they are stepped
+/// through and selected like matches.
+pub struct ComparePanel {
+    // `None` once the results no longer come from a comparison
+    compared: Option<Compared>,
+    highlight: bool,
+}
+
+impl Default for ComparePanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ComparePanel {
+    // Least time between looking up the other file's metadata, since
+    // `is_stale` is asked every frame for every tab
+    const STAT_INTERVAL: Duration = Duration::from_secs(1);
+
+    pub fn new() -> Self {
+        Self {
+            compared: None,
+            highlight: true,
+        }
+    }
+
+    fn stamp(path: &Path) -> Option<(u64, SystemTime)> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some((metadata.len(), metadata.modified().ok()?))
+    }
+
+    /// Records that the results are now the differences from `path`, for
+    /// the open file at file panel revision `revision`.
+    pub fn start(&mut self, path: PathBuf, revision: u64) {
+        self.compared = Some(Compared {
+            stamp: Self::stamp(&path),
+            checked: Instant::now(),
+            path,
+            revision,
+        });
+    }
+
+    /// Whether the results are differences to paint in the diff color
+    /// rather than as matches.
+    pub fn is_highlighted(&self) -> bool {
+        self.highlight && self.compared.is_some()
+    }
+
+    /// Whether either side changed since the comparison, so the listed
+    /// differences may point at the wrong bytes. `revision` is the open
+    /// file's, as in `start`. The other file is only looked at once every
+    /// `STAT_INTERVAL`.
+    pub fn is_stale(&mut self, revision: u64) -> bool {
+        let Some(c) = &mut self.compared else {
+            return false;
+        };
+        if c.revision != revision {
+            return true;
+        }
+        if c.checked.elapsed() < Self::STAT_INTERVAL {
+            return false;
+        }
+        c.checked = Instant::now();
+        Self::stamp(&c.path) != c.stamp
+    }
+
+    pub fn clear(&mut self) {
+        self.compared = None;
+    }
+
+    /// Returns the file the user picked to compare with. `has_file` is
+    /// whether there is an open file to compare.
+    pub fn render(&mut self, ui: &mut egui::Ui, has_file: bool) -> Option<PathBuf> {
+        let mut picked = None;
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Compare");
+                    if ui
+                        .add_enabled(has_file, egui::Button::new("Open..."))
+                        .on_hover_text("List where the open file differs from another one")
+                        .clicked()
+                    {
+                        picked = rfd::FileDialog::new().pick_file();
+                    }
+                    ui.checkbox(&mut self.highlight, "Highlight")
+                        .on_hover_text("Paint the differences in their own color");
+                });
+
+                match &self.compared {
+                    Some(compared) => {
+                        let name = compared
+                            .path
+                            .file_name()
+                            .unwrap_or(compared.path.as_os_str())
+                            .to_string_lossy();
+                        ui.label(format!("Differences from {} are in the results", name));
+                    }
+                    None => {
+                        ui.label("No file to compare with");
+                    }
+                }
+            });
+        });
+
+        picked
+    }
+}
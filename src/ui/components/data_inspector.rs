@@ -1,7 +1,16 @@
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 use eframe::egui;
 use egui_extras::{Column, TableBuilder};
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use crate::search::Endianness;
+use crate::ui::util::{OffsetFormat, WIN1252_80_9F, copy_offset, format_delta};
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Radix {
     Decimal,
     Hexadecimal,
@@ -20,23 +29,245 @@ impl std::fmt::Display for Radix {
     }
 }
 
+/// Instruction set the instruction row decodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+enum InstructionArch {
+    X86_16,
+    X86_32,
+    X86_64,
+}
+
+impl std::fmt::Display for InstructionArch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InstructionArch::X86_16 => write!(f, "x86-16"),
+            InstructionArch::X86_32 => write!(f, "x86-32"),
+            InstructionArch::X86_64 => write!(f, "x86-64"),
+        }
+    }
+}
+
+/// Settings of the rows that need more than the byte order and radix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+struct RowOptions {
+    /// Fractional bits of the signed fixed-point rows, Q1.15 and Q16.16 by default.
+    fixed16_frac_bits: u32,
+    fixed32_frac_bits: u32,
+    instruction_arch: InstructionArch,
+    /// Subtracted from the RVA rows' values, e.g. a PE image base to read VAs.
+    rva_base: u64,
+    /// Then added to give a file offset, e.g. a section's raw offset minus its RVA.
+    rva_delta: i64,
+}
+
+impl Default for RowOptions {
+    fn default() -> Self {
+        Self {
+            fixed16_frac_bits: 15,
+            fixed32_frac_bits: 16,
+            instruction_arch: InstructionArch::X86_64,
+            rva_base: 0,
+            rva_delta: 0,
+        }
+    }
+}
+
+/// One row of the inspector table.
+struct Interpretation {
+    data_type: String,
+    value: String,
+    /// Color preview painted before the value.
+    swatch: Option<egui::Color32>,
+    /// In-file offset the value points to, offered as a "Go" button.
+    jump_target: Option<usize>,
+}
+
+impl From<(String, String)> for Interpretation {
+    fn from((data_type, value): (String, String)) -> Self {
+        Self {
+            data_type,
+            value,
+            swatch: None,
+            jump_target: None,
+        }
+    }
+}
+
+/// The inspector's rows, in display order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, EnumIter, Serialize, Deserialize)]
+pub enum InspectorRow {
+    // Integer interpretations
+    U8,
+    I8,
+    U16,
+    I16,
+    U24,
+    I24,
+    U32,
+    I32,
+    U64,
+    I64,
+    U128,
+    I128,
+    Uleb128,
+    Sleb128,
+    // Float interpretations
+    F16,
+    Bf16,
+    F32,
+    F32Fields,
+    F64,
+    F64Fields,
+    // Fixed-point interpretations
+    Fixed16,
+    Fixed32,
+    // Timestamp interpretations
+    Unix32,
+    Unix64,
+    Unix64Millis,
+    FileTime,
+    DosDateTime,
+    // ASCII/Character interpretations
+    Ascii,
+    Utf8,
+    Utf16,
+    Utf32,
+    // Japanese multi-byte, with the `cjk` feature
+    ShiftJis,
+    EucJp,
+    CString,
+    PString8,
+    PString16,
+    PString32,
+    // Color interpretations
+    Rgb,
+    Rgba,
+    Argb,
+    // Pointer interpretations
+    Ptr32,
+    Ptr64,
+    Rva32,
+    Rva64,
+    // Machine code, with the `disasm` feature
+    Instruction,
+}
+
+impl std::fmt::Display for InspectorRow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use InspectorRow::*;
+        let s = match self {
+            U8 => "u8",
+            I8 => "i8",
+            U16 => "u16",
+            I16 => "i16",
+            U24 => "u24",
+            I24 => "i24",
+            U32 => "u32",
+            I32 => "i32",
+            U64 => "u64",
+            I64 => "i64",
+            U128 => "u128",
+            I128 => "i128",
+            Uleb128 => "ULEB128",
+            Sleb128 => "SLEB128",
+            F16 => "f16",
+            Bf16 => "bf16",
+            F32 => "f32",
+            F32Fields => "f32 fields",
+            F64 => "f64",
+            F64Fields => "f64 fields",
+            Fixed16 => "Fixed16",
+            Fixed32 => "Fixed32",
+            Unix32 => "time_t32",
+            Unix64 => "time_t64",
+            Unix64Millis => "time_ms64",
+            FileTime => "FILETIME",
+            DosDateTime => "DOS Time",
+            Ascii => "ASCII",
+            Utf8 => "UTF-8",
+            Utf16 => "UTF-16",
+            Utf32 => "UTF-32",
+            ShiftJis => "Shift-JIS",
+            EucJp => "EUC-JP",
+            CString => "CString",
+            PString8 => "PString(u8)",
+            PString16 => "PString(u16)",
+            PString32 => "PString(u32)",
+            Rgb => "RGB",
+            Rgba => "RGBA",
+            Argb => "ARGB",
+            Ptr32 => "Ptr32",
+            Ptr64 => "Ptr64",
+            Rva32 => "RVA32",
+            Rva64 => "RVA64",
+            Instruction => "Instruction",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl InspectorRow {
+    /// Whether the row reads differently in little- and big-endian order.
+    fn is_endian_dependent(self) -> bool {
+        use InspectorRow::*;
+        !matches!(self, U8 | I8 | Uleb128 | Sleb128 | Ascii | Utf8 | ShiftJis | EucJp | CString | PString8 | Rgb | Rgba | Argb | Instruction)
+    }
+
+    /// Whether this build can show the row.
+    fn is_available(&self) -> bool {
+        use InspectorRow::*;
+        (*self != Instruction || cfg!(feature = "disasm")) && (!matches!(self, ShiftJis | EucJp) || cfg!(feature = "cjk"))
+    }
+}
+
+// The inspector's layout and row settings are saved with the app; a saved
+// state from an older version keeps the defaults for settings added since
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
 pub struct DataInspector {
     little_endian: bool,
+    // Show little- and big-endian readings side by side
+    both_endians: bool,
     radix: Radix,
+    // Rows turned off by the user; rows added in later versions start shown
+    hidden_rows: BTreeSet<InspectorRow>,
+    row_options: RowOptions,
+}
+
+impl Default for DataInspector {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl DataInspector {
 
-    const EOF_MSG: &'static str = "No Data";
+    pub const EOF_MSG: &'static str = "No Data";
+    const INVALID_MSG: &'static str = "Invalid";
+    const MAX_STRING_LEN: usize = 256;
+    /// Bytes from the offset that the rows read: a shown string with its
+    /// longest length prefix, which covers every fixed-width row too.
+    pub const WINDOW_LEN: usize = 4 + Self::MAX_STRING_LEN;
+
+    /// The bytes of `data` the inspector reads at `offset`, `None` when the
+    /// offset is at or past the end.
+    pub fn window(data: &[u8], offset: usize) -> Option<&[u8]> {
+        let rest = data.get(offset..).filter(|rest| !rest.is_empty())?;
+        Some(&rest[..rest.len().min(Self::WINDOW_LEN)])
+    }
 
     pub fn new() -> Self {
         Self {
             little_endian: true,
+            both_endians: false,
             radix: Radix::Decimal,
+            hidden_rows: BTreeSet::new(),
+            row_options: RowOptions::default(),
         }
     }
 
-    fn format_number(value: u64, radix: Radix) -> String {
+    fn format_number(value: u128, radix: Radix) -> String {
         match radix {
             Radix::Decimal => format!("{}", value),
             Radix::Hexadecimal => format!("0x{:X}", value),
@@ -45,28 +276,28 @@ impl DataInspector {
         }
     }
 
-    fn format_signed_number(value: i64, radix: Radix) -> String {
+    fn format_signed_number(value: i128, radix: Radix) -> String {
         match radix {
             Radix::Decimal => format!("{}", value),
             Radix::Hexadecimal => {
                 if value < 0 {
-                    format!("-0x{:X}", (-value) as u64)
+                    format!("-0x{:X}", value.unsigned_abs())
                 } else {
-                    format!("0x{:X}", value as u64)
+                    format!("0x{:X}", value as u128)
                 }
             }
             Radix::Binary => {
                 if value < 0 {
-                    format!("-0b{:b}", (-value) as u64)
+                    format!("-0b{:b}", value.unsigned_abs())
                 } else {
-                    format!("0b{:b}", value as u64)
+                    format!("0b{:b}", value as u128)
                 }
             }
             Radix::Octal => {
                 if value < 0 {
-                    format!("-0o{:o}", (-value) as u64)
+                    format!("-0o{:o}", value.unsigned_abs())
                 } else {
-                    format!("0o{:o}", value as u64)
+                    format!("0o{:o}", value as u128)
                 }
             }
         }
@@ -85,7 +316,7 @@ impl DataInspector {
             }
         } else if abs_value == 0.0 {
             "0.0".to_string()
-        } else if abs_value >= 1e-4 && abs_value < 1e6 {
+        } else if (1e-4..1e6).contains(&abs_value) {
             // Use fixed-point notation for reasonable range
             let formatted = format!("{:.6}", value);
             // Remove trailing zeros after decimal point
@@ -101,6 +332,12 @@ impl DataInspector {
         }
     }
 
+    fn format_datetime(datetime: Option<DateTime<Utc>>) -> String {
+        datetime.map_or(Self::INVALID_MSG.into(), |dt| {
+            dt.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+        })
+    }
+
     fn intepret_ascii(b: &[u8]) -> (String, String) {
         // 1) ASCII control characters and their names
         const ASCII_CTRL_NAMES: [&str; 33] = [
@@ -109,43 +346,6 @@ impl DataInspector {
             "SUB", "ESC", "FS", "GS", "RS", "US", "DEL", // 0x7F
         ];
 
-        // 2) Windows-1252 special mappings for 0x80–0x9F (None for unmapped)
-        // Unmapped: 0x81, 0x8D, 0x8F, 0x90, 0x9D
-        const WIN1252_80_9F: [Option<char>; 32] = [
-            Some('€'),
-            None,
-            Some('‚'),
-            Some('ƒ'),
-            Some('„'),
-            Some('…'),
-            Some('†'),
-            Some('‡'),
-            Some('ˆ'),
-            Some('‰'),
-            Some('Š'),
-            Some('‹'),
-            Some('Œ'),
-            None,
-            Some('Ž'),
-            None,
-            None,
-            Some('‘'),
-            Some('’'),
-            Some('“'),
-            Some('”'),
-            Some('•'),
-            Some('–'),
-            Some('—'),
-            Some('˜'),
-            Some('™'),
-            Some('š'),
-            Some('›'),
-            Some('œ'),
-            None,
-            Some('ž'),
-            Some('Ÿ'),
-        ];
-
         // Helper function: wrap displayable character in single quotes
         fn quoted(c: char) -> String {
             format!("'{}'", c)
@@ -190,7 +390,7 @@ impl DataInspector {
             if b.is_empty() {
                 Self::EOF_MSG.into()
             } else {
-                DataInspector::format_number(b[0] as u64, radix)
+                DataInspector::format_number(b[0] as u128, radix)
             },
         )
     }
@@ -200,7 +400,7 @@ impl DataInspector {
             if b.is_empty() {
                 Self::EOF_MSG.into()
             } else {
-                DataInspector::format_signed_number(b[0] as i8 as i64, radix)
+                DataInspector::format_signed_number(b[0] as i8 as i128, radix)
             },
         )
     }
@@ -215,7 +415,7 @@ impl DataInspector {
         };
         (
             "u16".into(),
-            DataInspector::format_number(value as u64, radix),
+            DataInspector::format_number(value as u128, radix),
         )
     }
     fn intepret_i16(b: &[u8], radix: Radix, is_little_endian: bool) -> (String, String) {
@@ -229,7 +429,7 @@ impl DataInspector {
         };
         (
             "i16".into(),
-            DataInspector::format_signed_number(value as i64, radix),
+            DataInspector::format_signed_number(value as i128, radix),
         )
     }
     fn intepret_u24(b: &[u8], radix: Radix, is_little_endian: bool) -> (String, String) {
@@ -243,7 +443,7 @@ impl DataInspector {
         };
         (
             "u24".into(),
-            DataInspector::format_number(value as u64, radix),
+            DataInspector::format_number(value as u128, radix),
         )
     }
     fn intepret_i24(b: &[u8], radix: Radix, is_little_endian: bool) -> (String, String) {
@@ -263,7 +463,7 @@ impl DataInspector {
         };
         (
             "i24".into(),
-            DataInspector::format_signed_number(signed_value as i64, radix),
+            DataInspector::format_signed_number(signed_value as i128, radix),
         )
     }
     fn intepret_u32(b: &[u8], radix: Radix, is_little_endian: bool) -> (String, String) {
@@ -277,7 +477,7 @@ impl DataInspector {
         };
         (
             "u32".into(),
-            DataInspector::format_number(value as u64, radix),
+            DataInspector::format_number(value as u128, radix),
         )
     }
     fn intepret_i32(b: &[u8], radix: Radix, is_little_endian: bool) -> (String, String) {
@@ -291,7 +491,7 @@ impl DataInspector {
         };
         (
             "i32".into(),
-            DataInspector::format_signed_number(value as i64, radix),
+            DataInspector::format_signed_number(value as i128, radix),
         )
     }
     fn intepret_u64(b: &[u8], radix: Radix, is_little_endian: bool) -> (String, String) {
@@ -303,7 +503,10 @@ impl DataInspector {
         } else {
             u64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]])
         };
-        ("u64".into(), DataInspector::format_number(value, radix))
+        (
+            "u64".into(),
+            DataInspector::format_number(value as u128, radix),
+        )
     }
     fn intepret_i64(b: &[u8], radix: Radix, is_little_endian: bool) -> (String, String) {
         if b.len() < 8 {
@@ -316,6 +519,33 @@ impl DataInspector {
         };
         (
             "i64".into(),
+            DataInspector::format_signed_number(value as i128, radix),
+        )
+    }
+    fn intepret_u128(b: &[u8], radix: Radix, is_little_endian: bool) -> (String, String) {
+        if b.len() < 16 {
+            return ("u128".into(), Self::EOF_MSG.into());
+        }
+        let bytes: [u8; 16] = b[..16].try_into().unwrap();
+        let value = if is_little_endian {
+            u128::from_le_bytes(bytes)
+        } else {
+            u128::from_be_bytes(bytes)
+        };
+        ("u128".into(), DataInspector::format_number(value, radix))
+    }
+    fn intepret_i128(b: &[u8], radix: Radix, is_little_endian: bool) -> (String, String) {
+        if b.len() < 16 {
+            return ("i128".into(), Self::EOF_MSG.into());
+        }
+        let bytes: [u8; 16] = b[..16].try_into().unwrap();
+        let value = if is_little_endian {
+            i128::from_le_bytes(bytes)
+        } else {
+            i128::from_be_bytes(bytes)
+        };
+        (
+            "i128".into(),
             DataInspector::format_signed_number(value, radix),
         )
     }
@@ -368,6 +598,201 @@ impl DataInspector {
         ("f64".into(), DataInspector::format_float(value))
     }
 
+    /// Splits a `width`-byte IEEE-754 float into its sign bit, exponent
+    /// (biased, then unbiased) and mantissa, for checking how a value is encoded.
+    fn interpret_float_fields(b: &[u8], is_little_endian: bool, width: usize) -> (String, String) {
+        let (data_type, exp_bits, mantissa_bits) = match width {
+            4 => ("f32 fields", 8, 23),
+            _ => ("f64 fields", 11, 52),
+        };
+        if b.len() < width {
+            return (data_type.into(), Self::EOF_MSG.into());
+        }
+        let endianness = if is_little_endian { Endianness::LittleEndian } else { Endianness::BigEndian };
+        let raw = endianness.read_int(&b[..width], false).unwrap_or_default() as u64;
+        let sign = raw >> (exp_bits + mantissa_bits);
+        let exp_max = (1u64 << exp_bits) - 1;
+        let exp = (raw >> mantissa_bits) & exp_max;
+        let mantissa = raw & ((1u64 << mantissa_bits) - 1);
+        let bias = (exp_max >> 1) as i64;
+        let unbiased = match exp {
+            0 if mantissa == 0 => "zero".to_string(),
+            // Subnormals have no implicit leading 1 and use the lowest normal exponent
+            0 => format!("2^{} subnormal", 1 - bias),
+            _ if exp == exp_max && mantissa == 0 => "Inf".to_string(),
+            _ if exp == exp_max => "NaN".to_string(),
+            _ => format!("2^{}", exp as i64 - bias),
+        };
+        let value = format!(
+            "sign {}, exp {} ({}), mantissa 0x{:0w$X}",
+            sign,
+            exp,
+            unbiased,
+            mantissa,
+            w = (mantissa_bits as usize).div_ceil(4)
+        );
+        (data_type.into(), value)
+    }
+
+    /// Reads a signed `width`-byte integer as a fixed-point number with
+    /// `frac_bits` bits after the binary point, named `Qm.n` with the sign in `m`.
+    fn interpret_fixed(b: &[u8], is_little_endian: bool, width: usize, frac_bits: u32) -> (String, String) {
+        let bits = width as u32 * 8;
+        let frac_bits = frac_bits.min(bits - 1);
+        let data_type = format!("Q{}.{}", bits - frac_bits, frac_bits);
+        if b.len() < width {
+            return (data_type, Self::EOF_MSG.into());
+        }
+        let endianness = if is_little_endian { Endianness::LittleEndian } else { Endianness::BigEndian };
+        // `read_int` only fails for widths outside 1..=8 bytes
+        let raw = endianness.read_int(&b[..width], true).unwrap_or_default();
+        let value = raw as f64 / (1u64 << frac_bits) as f64;
+        (data_type, DataInspector::format_float(value))
+    }
+
+    fn interpret_unix32(b: &[u8], is_little_endian: bool) -> (String, String) {
+        if b.len() < 4 {
+            return ("time_t32".into(), Self::EOF_MSG.into());
+        }
+        // A 32-bit `time_t` is signed, so values with the top bit set are before 1970
+        let value = if is_little_endian {
+            i32::from_le_bytes([b[0], b[1], b[2], b[3]])
+        } else {
+            i32::from_be_bytes([b[0], b[1], b[2], b[3]])
+        };
+        (
+            "time_t32".into(),
+            DataInspector::format_datetime(DateTime::from_timestamp(value as i64, 0)),
+        )
+    }
+    fn interpret_unix64(b: &[u8], is_little_endian: bool) -> (String, String) {
+        if b.len() < 8 {
+            return ("time_t64".into(), Self::EOF_MSG.into());
+        }
+        let value = if is_little_endian {
+            i64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]])
+        } else {
+            i64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]])
+        };
+        (
+            "time_t64".into(),
+            DataInspector::format_datetime(DateTime::from_timestamp(value, 0)),
+        )
+    }
+    fn interpret_unix64_millis(b: &[u8], is_little_endian: bool) -> (String, String) {
+        if b.len() < 8 {
+            return ("time_ms64".into(), Self::EOF_MSG.into());
+        }
+        let value = if is_little_endian {
+            i64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]])
+        } else {
+            i64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]])
+        };
+        (
+            "time_ms64".into(),
+            DataInspector::format_datetime(DateTime::from_timestamp_millis(value)),
+        )
+    }
+
+    fn interpret_filetime(b: &[u8], is_little_endian: bool) -> (String, String) {
+        // 100-ns intervals between 1601-01-01 and 1970-01-01
+        const TICKS_PER_SECOND: u64 = 10_000_000;
+        const UNIX_EPOCH_SECONDS: i64 = 11_644_473_600;
+        if b.len() < 8 {
+            return ("FILETIME".into(), Self::EOF_MSG.into());
+        }
+        let value = if is_little_endian {
+            u64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]])
+        } else {
+            u64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]])
+        };
+        let secs = (value / TICKS_PER_SECOND) as i64 - UNIX_EPOCH_SECONDS;
+        let nanos = (value % TICKS_PER_SECOND) as u32 * 100;
+        (
+            "FILETIME".into(),
+            DataInspector::format_datetime(DateTime::from_timestamp(secs, nanos)),
+        )
+    }
+    fn interpret_dos_datetime(b: &[u8], is_little_endian: bool) -> (String, String) {
+        // Packed as a 16-bit time followed by a 16-bit date, as in ZIP and FAT
+        if b.len() < 4 {
+            return ("DOS Time".into(), Self::EOF_MSG.into());
+        }
+        let (time, date) = if is_little_endian {
+            (
+                u16::from_le_bytes([b[0], b[1]]),
+                u16::from_le_bytes([b[2], b[3]]),
+            )
+        } else {
+            (
+                u16::from_be_bytes([b[0], b[1]]),
+                u16::from_be_bytes([b[2], b[3]]),
+            )
+        };
+        let date = NaiveDate::from_ymd_opt(
+            1980 + (date >> 9) as i32,
+            ((date >> 5) & 0x0F) as u32,
+            (date & 0x1F) as u32,
+        );
+        let time = NaiveTime::from_hms_opt(
+            (time >> 11) as u32,
+            ((time >> 5) & 0x3F) as u32,
+            (time & 0x1F) as u32 * 2,
+        );
+        let value = match (date, time) {
+            (Some(date), Some(time)) => date
+                .and_time(time)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+            _ => Self::INVALID_MSG.into(),
+        };
+        ("DOS Time".into(), value)
+    }
+
+    /// Decodes the LEB128 groups at the start of `b` into
+    /// `(value bits, bits read, bytes consumed)`.
+    fn read_leb128(b: &[u8]) -> Result<(u128, u32, usize), &'static str> {
+        // 19 groups of 7 bits cover a 128-bit value
+        const MAX_BYTES: usize = 19;
+        let mut value = 0u128;
+        let mut shift = 0u32;
+        for (i, &byte) in b.iter().enumerate() {
+            if i == MAX_BYTES {
+                return Err(Self::INVALID_MSG);
+            }
+            value |= ((byte & 0x7F) as u128).checked_shl(shift).unwrap_or(0);
+            shift += 7;
+            if byte & 0x80 == 0 {
+                return Ok((value, shift, i + 1));
+            }
+        }
+        Err(Self::EOF_MSG)
+    }
+    fn interpret_uleb128(b: &[u8], radix: Radix) -> (String, String) {
+        match Self::read_leb128(b) {
+            Ok((value, _, len)) => (
+                format!("ULEB128({})", len),
+                DataInspector::format_number(value, radix),
+            ),
+            Err(msg) => ("ULEB128".into(), msg.into()),
+        }
+    }
+    fn interpret_sleb128(b: &[u8], radix: Radix) -> (String, String) {
+        match Self::read_leb128(b) {
+            Ok((mut value, shift, len)) => {
+                // Sign extend from the last group's sign bit
+                if shift < 128 && value & (1 << (shift - 1)) != 0 {
+                    value |= !0u128 << shift;
+                }
+                (
+                    format!("SLEB128({})", len),
+                    DataInspector::format_signed_number(value as i128, radix),
+                )
+            }
+            Err(msg) => ("SLEB128".into(), msg.into()),
+        }
+    }
+
     fn interpret_utf8(b: &[u8]) -> (String, String) {
         if b.is_empty() {
             return ("UTF-8".into(), Self::EOF_MSG.into());
@@ -404,6 +829,93 @@ impl DataInspector {
         }
     }
 
+    /// Bytes in the Shift-JIS or EUC-JP character that starts with `lead`.
+    fn cjk_char_len(row: InspectorRow, lead: u8) -> usize {
+        match (row, lead) {
+            (InspectorRow::ShiftJis, 0x81..=0x9F | 0xE0..=0xFC) => 2,
+            // Half-width katakana and JIS X 0208, then JIS X 0212
+            (InspectorRow::EucJp, 0x8E | 0xA1..=0xFE) => 2,
+            (InspectorRow::EucJp, 0x8F) => 3,
+            _ => 1,
+        }
+    }
+
+    fn interpret_cjk(row: InspectorRow, b: &[u8]) -> (String, String) {
+        let Some(&lead) = b.first() else {
+            return (row.to_string(), Self::EOF_MSG.into());
+        };
+        let len = Self::cjk_char_len(row, lead);
+        if b.len() < len {
+            return (row.to_string(), Self::EOF_MSG.into());
+        }
+        (format!("{}({})", row, len), Self::decode_cjk(row, &b[..len]))
+    }
+
+    #[cfg(feature = "cjk")]
+    fn decode_cjk(row: InspectorRow, b: &[u8]) -> String {
+        let encoding = if row == InspectorRow::ShiftJis { encoding_rs::SHIFT_JIS } else { encoding_rs::EUC_JP };
+        // Invalid sequences decode to the replacement character
+        let (text, _) = encoding.decode_without_bom_handling(b);
+        match text.chars().next() {
+            Some(ch) if ch != '\u{FFFD}' => format!("'{}'", ch),
+            _ => "\u{FFFD}".into(),
+        }
+    }
+
+    #[cfg(not(feature = "cjk"))]
+    fn decode_cjk(_row: InspectorRow, _b: &[u8]) -> String {
+        "Built without the cjk feature".into()
+    }
+
+    /// Renders string bytes lossily, with control characters shown as `.`.
+    fn printable_string(b: &[u8]) -> String {
+        let s: String = String::from_utf8_lossy(b)
+            .chars()
+            .map(|c| if c.is_control() { '.' } else { c })
+            .collect();
+        format!("'{}'", s)
+    }
+
+    fn interpret_cstring(b: &[u8]) -> (String, String) {
+        if b.is_empty() {
+            return ("CString".into(), Self::EOF_MSG.into());
+        }
+        let window = &b[..b.len().min(Self::MAX_STRING_LEN)];
+        match memchr::memchr(0, window) {
+            Some(len) => (
+                format!("CString({})", len),
+                Self::printable_string(&window[..len]),
+            ),
+            // No terminator within the cap (or before EOF)
+            None => (
+                format!("CString({}+)", window.len()),
+                Self::printable_string(window),
+            ),
+        }
+    }
+
+    /// Reads a `width`-byte length and then that many bytes of string, as
+    /// Pascal-style strings are stored. Only the first `MAX_STRING_LEN` bytes
+    /// are shown, and only those need to be in the data.
+    fn interpret_pstring(b: &[u8], is_little_endian: bool, width: usize) -> (String, String) {
+        let bits = width * 8;
+        if b.len() < width {
+            return (format!("PString(u{})", bits), Self::EOF_MSG.into());
+        }
+        let endianness = if is_little_endian { Endianness::LittleEndian } else { Endianness::BigEndian };
+        // `read_int` only fails for widths outside 1..=8 bytes
+        let len = endianness.read_int(&b[..width], false).unwrap_or_default() as usize;
+        let data_type = format!("PString(u{}, {})", bits, len);
+        let Some(bytes) = b[width..].get(..len.min(Self::MAX_STRING_LEN)) else {
+            return (data_type, Self::EOF_MSG.into());
+        };
+        let mut value = Self::printable_string(bytes);
+        if len > Self::MAX_STRING_LEN {
+            value.push('…');
+        }
+        (data_type, value)
+    }
+
     fn interpret_utf16(b: &[u8], is_little_endian: bool) -> (String, String) {
         if b.len() < 2 {
             return ("UTF-16".into(), Self::EOF_MSG.into());
@@ -460,39 +972,229 @@ impl DataInspector {
         }
     }
 
-    fn get_data_interpretations(&self, data: &[u8], offset: Option<usize>) -> [(String, String); 18] {
-        let data_slice = offset.map_or_else( || &[] as &[u8], |off| &data[off..]);
-        [
-            // Integer interpretations
-            Self::intepret_u8(data_slice, self.radix),
-            Self::intepret_i8(data_slice, self.radix),
-            Self::intepret_u16(data_slice, self.radix, self.little_endian),
-            Self::intepret_i16(data_slice, self.radix, self.little_endian),
-            Self::intepret_u24(data_slice, self.radix, self.little_endian),
-            Self::intepret_i24(data_slice, self.radix, self.little_endian),
-            Self::intepret_u32(data_slice, self.radix, self.little_endian),
-            Self::intepret_i32(data_slice, self.radix, self.little_endian),
-            Self::intepret_u64(data_slice, self.radix, self.little_endian),
-            Self::intepret_i64(data_slice, self.radix, self.little_endian),
-            // Float interpretations
-            Self::interpret_f16(data_slice, self.little_endian),
-            Self::interpret_bf16(data_slice, self.little_endian),
-            Self::interpret_f32(data_slice, self.little_endian),
-            Self::interpret_f64(data_slice, self.little_endian),
-            // ASCII/Character interpretations
-            Self::intepret_ascii(data_slice),
-            Self::interpret_utf8(data_slice),
-            Self::interpret_utf16(data_slice, self.little_endian),
-            Self::interpret_utf32(data_slice, self.little_endian),
-        ]
+    /// Reads `b` as color channels; `order` names the channel stored in each byte.
+    fn interpret_color(b: &[u8], order: &str) -> Interpretation {
+        let n = order.len();
+        if b.len() < n {
+            return (order.into(), Self::EOF_MSG.into()).into();
+        }
+        let mut rgba = [0, 0, 0, 0xFF];
+        for (&byte, channel) in b.iter().zip(order.chars()) {
+            let index = "RGBA".find(channel).unwrap();
+            rgba[index] = byte;
+        }
+        let [r, g, b, a] = rgba;
+        let value = if n == 3 {
+            format!("#{:02X}{:02X}{:02X}", r, g, b)
+        } else {
+            format!("#{:02X}{:02X}{:02X}{:02X}", r, g, b, a)
+        };
+        Interpretation {
+            data_type: order.into(),
+            value,
+            swatch: Some(egui::Color32::from_rgba_unmultiplied(r, g, b, a)),
+            jump_target: None,
+        }
+    }
+
+    /// The `width`-byte unsigned value at the start of `b`, `None` if it runs short.
+    fn read_pointer(b: &[u8], little_endian: bool, width: usize) -> Option<u64> {
+        let b = b.get(..width)?;
+        let mut bytes = [0u8; 8];
+        if little_endian {
+            bytes[..width].copy_from_slice(b);
+            Some(u64::from_le_bytes(bytes))
+        } else {
+            bytes[8 - width..].copy_from_slice(b);
+            Some(u64::from_be_bytes(bytes))
+        }
+    }
+
+    /// Where file offset `offset` is in the loaded data, if it is loaded.
+    /// Offsets only match data offsets when the whole file is loaded.
+    fn data_offset(offset: i128, file_len: usize, offset_base: OffsetFormat) -> Option<usize> {
+        usize::try_from(offset)
+            .ok()
+            .and_then(|t| t.checked_sub(offset_base.origin))
+            .filter(|&t| t < file_len)
+    }
+
+    /// Reads a `width`-byte unsigned value as an offset into the file.
+    fn interpret_pointer(b: &[u8], little_endian: bool, width: usize, file_len: usize, offset_base: OffsetFormat) -> Interpretation {
+        let data_type = format!("Ptr{}", width * 8);
+        let Some(raw) = Self::read_pointer(b, little_endian, width) else {
+            return (data_type, Self::EOF_MSG.into()).into();
+        };
+        let target = Self::data_offset(raw.into(), file_len, offset_base);
+        let value = match (target, usize::try_from(raw)) {
+            (Some(t), _) => offset_base.format(t),
+            (None, Ok(raw)) => format!("{} (outside file)", offset_base.base.format(raw)),
+            (None, Err(_)) => format!("0x{:X} (out of range)", raw),
+        };
+        Interpretation {
+            data_type,
+            value,
+            swatch: None,
+            jump_target: target,
+        }
+    }
+
+    /// Reads a `width`-byte unsigned value as an address relative to
+    /// `options.rva_base`, moved by `options.rva_delta` into the file.
+    fn interpret_rva(b: &[u8], little_endian: bool, width: usize, file_len: usize, offset_base: OffsetFormat, options: RowOptions) -> Interpretation {
+        let data_type = format!("RVA{}", width * 8);
+        let Some(raw) = Self::read_pointer(b, little_endian, width) else {
+            return (data_type, Self::EOF_MSG.into()).into();
+        };
+        let file_offset = i128::from(raw) - i128::from(options.rva_base) + i128::from(options.rva_delta);
+        let target = Self::data_offset(file_offset, file_len, offset_base);
+        let resolved = match target {
+            Some(t) => offset_base.format(t),
+            None => "outside file".into(),
+        };
+        let raw = match usize::try_from(raw) {
+            Ok(raw) => offset_base.base.format(raw),
+            Err(_) => format!("0x{:X} (out of range)", raw),
+        };
+        Interpretation {
+            data_type,
+            value: format!("{} → {}", raw, resolved),
+            swatch: None,
+            jump_target: target,
+        }
+    }
+
+    /// Decodes one instruction at the start of `b` and shows it with its length.
+    #[cfg(feature = "disasm")]
+    fn interpret_instruction(b: &[u8], arch: InstructionArch) -> (String, String) {
+        use iced_x86::{Decoder, DecoderError, DecoderOptions, Formatter, NasmFormatter};
+
+        let data_type = arch.to_string();
+        let bitness = match arch {
+            InstructionArch::X86_16 => 16,
+            InstructionArch::X86_32 => 32,
+            InstructionArch::X86_64 => 64,
+        };
+        // No x86 instruction is longer than 15 bytes
+        let bytes = &b[..b.len().min(15)];
+        let mut decoder = Decoder::new(bitness, bytes, DecoderOptions::NONE);
+        let instruction = decoder.decode();
+        if instruction.is_invalid() {
+            // Running out of bytes, e.g. at the end of the file, is not bad code
+            let msg = if decoder.last_error() == DecoderError::NoMoreBytes { Self::EOF_MSG } else { Self::INVALID_MSG };
+            return (data_type, msg.into());
+        }
+        let mut text = String::new();
+        NasmFormatter::new().format(&instruction, &mut text);
+        (data_type, format!("{} ({} bytes)", text, instruction.len()))
+    }
+
+    #[cfg(not(feature = "disasm"))]
+    fn interpret_instruction(_b: &[u8], arch: InstructionArch) -> (String, String) {
+        (arch.to_string(), "Built without the disasm feature".into())
+    }
+
+    fn interpret(row: InspectorRow, b: &[u8], radix: Radix, little_endian: bool, file_len: usize, offset_base: OffsetFormat, options: RowOptions) -> Interpretation {
+        use InspectorRow::*;
+        let le = little_endian;
+        match row {
+            U8 => Self::intepret_u8(b, radix).into(),
+            I8 => Self::intepret_i8(b, radix).into(),
+            U16 => Self::intepret_u16(b, radix, le).into(),
+            I16 => Self::intepret_i16(b, radix, le).into(),
+            U24 => Self::intepret_u24(b, radix, le).into(),
+            I24 => Self::intepret_i24(b, radix, le).into(),
+            U32 => Self::intepret_u32(b, radix, le).into(),
+            I32 => Self::intepret_i32(b, radix, le).into(),
+            U64 => Self::intepret_u64(b, radix, le).into(),
+            I64 => Self::intepret_i64(b, radix, le).into(),
+            U128 => Self::intepret_u128(b, radix, le).into(),
+            I128 => Self::intepret_i128(b, radix, le).into(),
+            Uleb128 => Self::interpret_uleb128(b, radix).into(),
+            Sleb128 => Self::interpret_sleb128(b, radix).into(),
+            F16 => Self::interpret_f16(b, le).into(),
+            Bf16 => Self::interpret_bf16(b, le).into(),
+            F32 => Self::interpret_f32(b, le).into(),
+            F32Fields => Self::interpret_float_fields(b, le, 4).into(),
+            F64 => Self::interpret_f64(b, le).into(),
+            F64Fields => Self::interpret_float_fields(b, le, 8).into(),
+            Fixed16 => Self::interpret_fixed(b, le, 2, options.fixed16_frac_bits).into(),
+            Fixed32 => Self::interpret_fixed(b, le, 4, options.fixed32_frac_bits).into(),
+            Unix32 => Self::interpret_unix32(b, le).into(),
+            Unix64 => Self::interpret_unix64(b, le).into(),
+            Unix64Millis => Self::interpret_unix64_millis(b, le).into(),
+            FileTime => Self::interpret_filetime(b, le).into(),
+            DosDateTime => Self::interpret_dos_datetime(b, le).into(),
+            Ascii => Self::intepret_ascii(b).into(),
+            Utf8 => Self::interpret_utf8(b).into(),
+            Utf16 => Self::interpret_utf16(b, le).into(),
+            Utf32 => Self::interpret_utf32(b, le).into(),
+            ShiftJis | EucJp => Self::interpret_cjk(row, b).into(),
+            CString => Self::interpret_cstring(b).into(),
+            PString8 => Self::interpret_pstring(b, le, 1).into(),
+            PString16 => Self::interpret_pstring(b, le, 2).into(),
+            PString32 => Self::interpret_pstring(b, le, 4).into(),
+            Rgb => Self::interpret_color(b, "RGB"),
+            Rgba => Self::interpret_color(b, "RGBA"),
+            Argb => Self::interpret_color(b, "ARGB"),
+            Ptr32 => Self::interpret_pointer(b, le, 4, file_len, offset_base),
+            Ptr64 => Self::interpret_pointer(b, le, 8, file_len, offset_base),
+            Rva32 => Self::interpret_rva(b, le, 4, file_len, offset_base, options),
+            Rva64 => Self::interpret_rva(b, le, 8, file_len, offset_base, options),
+            Instruction => Self::interpret_instruction(b, options.instruction_arch).into(),
+        }
+    }
+
+    /// Reads the start of `b` as `row` would show it, for other panels
+    /// decoding the same types. `file_len` bounds pointer rows; fixed-point
+    /// and instruction rows use their default settings.
+    pub fn decode(row: InspectorRow, b: &[u8], radix: Radix, little_endian: bool, file_len: usize, offset_base: OffsetFormat) -> String {
+        Self::interpret(row, b, radix, little_endian, file_len, offset_base, RowOptions::default()).value
+    }
+
+    /// Every shown row, read with the chosen byte order. With both byte orders shown,
+    /// rows that depend on it also get their big-endian reading.
+    fn get_data_interpretations(&self, window: &[u8], file_len: usize, offset_base: OffsetFormat) -> Vec<(Interpretation, Option<Interpretation>)> {
+        let little_endian = self.little_endian || self.both_endians;
+        InspectorRow::iter()
+            .filter(|row| row.is_available() && !self.hidden_rows.contains(row))
+            .map(|row| {
+                let value = Self::interpret(row, window, self.radix, little_endian, file_len, offset_base, self.row_options);
+                let big_endian = (self.both_endians && row.is_endian_dependent())
+                    .then(|| Self::interpret(row, window, self.radix, false, file_len, offset_base, self.row_options));
+                (value, big_endian)
+            })
+            .collect()
+    }
+
+    /// Value label that copies its text to the clipboard when clicked.
+    fn copyable_value(ui: &mut egui::Ui, value: String) {
+        if value == Self::EOF_MSG {
+            ui.label(value);
+            return;
+        }
+        let resp = ui
+            .add(egui::Label::new(&value).sense(egui::Sense::click()))
+            .on_hover_text("Click to copy");
+        if resp.clicked() {
+            ui.ctx().copy_text(value);
+        }
     }
 
+    /// Shows the rows for `window`, the bytes at `selected_offset` as given
+    /// by `window()`. `None` reads as no data. `file_len` bounds pointer rows.
+    /// `relative_origin` is the open file's origin for "Offset (rel)", set
+    /// and cleared from here.
     pub fn render(
         &mut self,
         ui: &mut egui::Ui,
         selected_offset: Option<usize>,
-        file_data: Option<&[u8]>,
-    ) {
+        window: Option<&[u8]>,
+        file_len: usize,
+        offset_base: OffsetFormat,
+        relative_origin: &mut Option<usize>,
+    ) -> Option<usize> {
+        let mut jump = None;
         // println!("Data Inspector Available width: {}", ui.available_width());
         
         let _resp = egui::Frame::group(ui.style())
@@ -506,8 +1208,12 @@ impl DataInspector {
 
                 ui.horizontal(|ui| {
                     // Endianness radio buttons
-                    ui.radio_value(&mut self.little_endian, true, "LE");
-                    ui.radio_value(&mut self.little_endian, false, "BE");
+                    ui.add_enabled_ui(!self.both_endians, |ui| {
+                        ui.radio_value(&mut self.little_endian, true, "LE");
+                        ui.radio_value(&mut self.little_endian, false, "BE");
+                    });
+                    ui.checkbox(&mut self.both_endians, "Both")
+                        .on_hover_text("Show little- and big-endian values side by side");
 
                     ui.separator();
 
@@ -520,56 +1226,310 @@ impl DataInspector {
                             ui.selectable_value(&mut self.radix, Radix::Binary, "Binary");
                             ui.selectable_value(&mut self.radix, Radix::Octal, "Octal");
                         });
+
+                    ui.separator();
+
+                    ui.menu_button("Rows", |ui| {
+                        if ui.button("Show all").clicked() {
+                            self.hidden_rows.clear();
+                        }
+                        ui.separator();
+                        egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                            for row in InspectorRow::iter().filter(InspectorRow::is_available) {
+                                let mut shown = !self.hidden_rows.contains(&row);
+                                if ui.checkbox(&mut shown, row.to_string()).changed() {
+                                    if shown {
+                                        self.hidden_rows.remove(&row);
+                                    } else {
+                                        self.hidden_rows.insert(row);
+                                    }
+                                }
+                            }
+                        });
+                        ui.separator();
+                        ui.horizontal(|ui| {
+                            ui.label("Fixed16 fraction bits:");
+                            ui.add(egui::DragValue::new(&mut self.row_options.fixed16_frac_bits).range(0..=15));
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Fixed32 fraction bits:");
+                            ui.add(egui::DragValue::new(&mut self.row_options.fixed32_frac_bits).range(0..=31));
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("RVA image base:");
+                            ui.add(egui::DragValue::new(&mut self.row_options.rva_base).hexadecimal(1, false, true).prefix("0x"))
+                                .on_hover_text("Subtracted from RVA values, to read virtual addresses");
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("RVA file delta:");
+                            ui.add(egui::DragValue::new(&mut self.row_options.rva_delta).hexadecimal(1, false, true).prefix("0x"))
+                                .on_hover_text("Added after the base to give a file offset, e.g. a section's raw offset minus its RVA");
+                        });
+                        if InspectorRow::Instruction.is_available() {
+                            ui.horizontal(|ui| {
+                                ui.label("Instruction set:");
+                                egui::ComboBox::from_id_salt("DataInspector.InstructionArch")
+                                    .selected_text(self.row_options.instruction_arch.to_string())
+                                    .show_ui(ui, |ui| {
+                                        for arch in InstructionArch::iter() {
+                                            ui.selectable_value(&mut self.row_options.instruction_arch, arch, arch.to_string());
+                                        }
+                                    });
+                            });
+                        }
+                    });
                 });
 
                 ui.separator();
 
-                let data = file_data.unwrap_or(&[]);
-
-                if selected_offset.is_some_and(|v| v >= data.len()) {
-                    panic!("Impossible!");
-                }
+                // A stale selection past the end (e.g. after a reload) reads as no data
+                let in_file = window.is_some();
                 ui.horizontal(|ui| {
                     ui.label("Offset:");
-                    ui.label(selected_offset.map_or("N/A".into(), |off| {
+                    let offset_label = ui.label(selected_offset.map_or("N/A".into(), |off| {
                         format!(
-                            "0x{:08X} ({})",
-                            off, off
+                            "{} ({}){}",
+                            offset_base.format(off),
+                            offset_base.other().format(off),
+                            if in_file { "" } else { " past end of file" }
                         )
                     }));
+                    if let Some(off) = selected_offset {
+                        offset_label.context_menu(|ui| copy_offset(ui, off, offset_base));
+                    }
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if relative_origin.is_some() && ui.small_button("Clear origin").clicked() {
+                            *relative_origin = None;
+                        }
+                        if ui
+                            .add_enabled(selected_offset.is_some(), egui::Button::new("Set origin here").small())
+                            .on_hover_text("Measure the offsets of later selections from this one")
+                            .clicked()
+                        {
+                            *relative_origin = selected_offset;
+                        }
+                    });
                 });
+                if let Some(origin) = *relative_origin {
+                    ui.horizontal(|ui| {
+                        ui.label("Offset (rel):");
+                        ui.label(selected_offset.map_or("N/A".into(), |off| format_delta(origin, off)))
+                            .on_hover_text(format!("From the origin at {}", offset_base.format(origin)));
+                    });
+                }
 
 
                 ui.separator(); 
 
-                let interpretations = self.get_data_interpretations(data, selected_offset);
-                let table = TableBuilder::new(ui)
+                let interpretations = self.get_data_interpretations(window.unwrap_or_default(), file_len, offset_base);
+                let both_endians = self.both_endians;
+                let mut table = TableBuilder::new(ui)
                     .striped(true)
-                    .column(Column::exact(80.0)) // Type
-                    .column(Column::remainder()); // Value
+                    .column(Column::exact(80.0)); // Type
+                if both_endians {
+                    table = table
+                        .column(Column::remainder().clip(true)) // LE value
+                        .column(Column::remainder().clip(true)); // BE value
+                } else {
+                    table = table.column(Column::remainder()); // Value
+                }
+                let value_cell = |ui: &mut egui::Ui, interpretation: Interpretation, jump: &mut Option<usize>| {
+                    if let Some(color) = interpretation.swatch {
+                        let (rect, _) = ui.allocate_exact_size(
+                            egui::vec2(14.0, 14.0),
+                            egui::Sense::hover(),
+                        );
+                        ui.painter().rect_filled(rect, 2.0, color);
+                    }
+                    Self::copyable_value(ui, interpretation.value);
+                    if let Some(target) = interpretation.jump_target
+                        && ui.small_button("Go").clicked()
+                    {
+                        *jump = Some(target);
+                    }
+                };
                 table
                     .header(20.0, |mut header| {
                         header.col(|ui| {
                             ui.strong("Type");
                         });
-                        header.col(|ui| {
-                            ui.strong("Value");
-                        });
+                        if both_endians {
+                            header.col(|ui| {
+                                ui.strong("LE");
+                            });
+                            header.col(|ui| {
+                                ui.strong("BE");
+                            });
+                        } else {
+                            header.col(|ui| {
+                                ui.strong("Value");
+                            });
+                        }
                     })
                     .body(|mut body| {
-                        for (data_type, value) in interpretations {
+                        for (row_data, big_endian) in interpretations {
                             body.row(18.0, |mut row| {
                                 row.col(|ui| {
-                                    ui.label(&data_type);
-                                });
-                                row.col(|ui| {
-                                    ui.label(&value);
+                                    ui.label(&row_data.data_type);
                                 });
+                                row.col(|ui| value_cell(ui, row_data, &mut jump));
+                                if both_endians {
+                                    row.col(|ui| {
+                                        if let Some(big_endian) = big_endian {
+                                            value_cell(ui, big_endian, &mut jump);
+                                        }
+                                    });
+                                }
                             });
                         }
                     });
             });
         });
         // println!("Data Inspector used width: {}", _resp.response.rect.width());
+        jump
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::util::OffsetBase;
+    use InspectorRow::*;
+
+    fn decode(row: InspectorRow, b: &[u8], little_endian: bool) -> String {
+        DataInspector::decode(row, b, Radix::Decimal, little_endian, 0x100, OffsetBase::Hexadecimal.into())
+    }
+
+    /// Bytes `row` needs from the start of `FIXTURE` before it reads as more than no data.
+    fn needed(row: InspectorRow) -> usize {
+        match row {
+            U8 | I8 | Uleb128 | Sleb128 | Ascii | Utf8 | ShiftJis | EucJp | CString => 1,
+            U16 | I16 | F16 | Bf16 | Fixed16 | Utf16 | Instruction => 2,
+            U24 | I24 | Rgb => 3,
+            U32 | I32 | F32 | F32Fields | Fixed32 | Unix32 | DosDateTime | Utf32 | Rgba | Argb | Ptr32 | Rva32 | PString8 => 4,
+            PString16 => 5,
+            PString32 => 7,
+            U64 | I64 | F64 | F64Fields | Unix64 | Unix64Millis | FileTime | Ptr64 | Rva64 => 8,
+            U128 | I128 => 16,
+        }
+    }
+
+    // A length of 3 in every prefix width, then "abc" and filler. `03 00`
+    // also decodes as an x86 instruction
+    const FIXTURE: [u8; 16] = [3, 0, 0, 0, b'a', b'b', b'c', 0, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47, 0x48];
+
+    #[test]
+    fn test_every_row_with_short_input() {
+        for row in InspectorRow::iter().filter(InspectorRow::is_available) {
+            for len in 0..=FIXTURE.len() {
+                for little_endian in [true, false] {
+                    let value = decode(row, &FIXTURE[..len], little_endian);
+                    // Big-endian lengths of the fixture are huge, so those strings only ever run short
+                    let short = len < needed(row) || (!little_endian && matches!(row, PString16 | PString32));
+                    assert_eq!(value == DataInspector::EOF_MSG, short, "{} over {} bytes, little endian: {}", row, len, little_endian);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_integer_rows() {
+        let b = [0xFE, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF];
+        assert_eq!(decode(U8, &b, true), "254");
+        assert_eq!(decode(I8, &b, true), "-2");
+        assert_eq!(decode(U16, &b, true), "65534");
+        assert_eq!(decode(U16, &b, false), "65279");
+        assert_eq!(decode(I16, &b, true), "-2");
+        assert_eq!(decode(I32, &b, true), "-2");
+        assert_eq!(decode(I64, &b, true), "-2");
+        assert_eq!(decode(Uleb128, &[0xE5, 0x8E, 0x26], true), "624485");
+        assert_eq!(decode(Sleb128, &[0xC0, 0xBB, 0x78], true), "-123456");
+    }
+
+    #[test]
+    fn test_unix32_row() {
+        assert_eq!(decode(Unix32, &0x7FFF_FFFFu32.to_le_bytes(), true), "2038-01-19T03:14:07Z");
+        assert_eq!(decode(Unix32, &(-86400i32).to_be_bytes(), false), "1969-12-31T00:00:00Z");
+    }
+
+    #[test]
+    fn test_float_field_rows() {
+        assert_eq!(decode(F32Fields, &1.5f32.to_le_bytes(), true), "sign 0, exp 127 (2^0), mantissa 0x400000");
+        assert_eq!(decode(F32Fields, &(-0.25f32).to_be_bytes(), false), "sign 1, exp 125 (2^-2), mantissa 0x000000");
+        assert_eq!(decode(F32Fields, &f32::MIN_POSITIVE.to_le_bytes(), true), "sign 0, exp 1 (2^-126), mantissa 0x000000");
+        assert_eq!(decode(F32Fields, &1u32.to_le_bytes(), true), "sign 0, exp 0 (2^-126 subnormal), mantissa 0x000001");
+        assert_eq!(decode(F32Fields, &f32::NAN.to_le_bytes(), true), "sign 0, exp 255 (NaN), mantissa 0x400000");
+        assert_eq!(decode(F64Fields, &f64::NEG_INFINITY.to_le_bytes(), true), "sign 1, exp 2047 (Inf), mantissa 0x0000000000000");
+        assert_eq!(decode(F64Fields, &0.0f64.to_le_bytes(), true), "sign 0, exp 0 (zero), mantissa 0x0000000000000");
+        assert_eq!(decode(F64Fields, &3.0f64.to_le_bytes(), true), "sign 0, exp 1024 (2^1), mantissa 0x8000000000000");
+    }
+
+    #[test]
+    fn test_string_rows() {
+        assert_eq!(decode(PString8, &FIXTURE, true), "'...'");
+        assert_eq!(decode(PString16, &[3, 0, b'a', b'b', b'c'], true), "'abc'");
+        assert_eq!(decode(PString16, &[0, 3, b'a', b'b', b'c'], false), "'abc'");
+        assert_eq!(decode(PString32, &FIXTURE, true), "'abc'");
+        assert_eq!(decode(PString8, &[0], true), "''");
+        assert_eq!(decode(CString, b"ab\0cd", true), "'ab'");
+        assert_eq!(decode(CString, b"abcd", true), "'abcd'");
+
+        // Long strings are cut at the cap, which is all that has to be there
+        let mut long = vec![0xFF, 0xFF];
+        long.extend(std::iter::repeat_n(b'x', DataInspector::MAX_STRING_LEN));
+        let value = decode(PString16, &long, true);
+        assert!(value.ends_with("'…"), "{}", value);
+    }
+
+    #[test]
+    fn test_cjk_rows() {
+        assert_eq!(DataInspector::cjk_char_len(ShiftJis, b'a'), 1);
+        assert_eq!(DataInspector::cjk_char_len(ShiftJis, 0xB1), 1);
+        assert_eq!(DataInspector::cjk_char_len(ShiftJis, 0x93), 2);
+        assert_eq!(DataInspector::cjk_char_len(EucJp, 0x8E), 2);
+        assert_eq!(DataInspector::cjk_char_len(EucJp, 0x8F), 3);
+        assert_eq!(DataInspector::cjk_char_len(EucJp, 0xC6), 2);
+        assert_eq!(decode(ShiftJis, &[0x93], true), DataInspector::EOF_MSG);
+
+        #[cfg(feature = "cjk")]
+        {
+            assert_eq!(decode(ShiftJis, &[0x93, 0xFA], true), "'日'");
+            assert_eq!(decode(ShiftJis, &[0xB1], true), "'ｱ'");
+            assert_eq!(decode(EucJp, &[0xC6, 0xFC], true), "'日'");
+            // A lead byte without a valid trail byte
+            assert_eq!(decode(ShiftJis, &[0x93, b' '], true), "\u{FFFD}");
+            assert_eq!(decode(ShiftJis, &[0xFF], true), "\u{FFFD}");
+        }
+    }
+
+    #[test]
+    fn test_rva_rows() {
+        let format = OffsetBase::Hexadecimal.into();
+        let rva = |b: &[u8], rva_base, rva_delta| {
+            let options = RowOptions { rva_base, rva_delta, ..RowOptions::default() };
+            let row = DataInspector::interpret(Rva32, b, Radix::Decimal, true, 0x100, format, options);
+            (row.value, row.jump_target)
+        };
+        let b = [0x10, 0x20, 0, 0];
+        assert_eq!(rva(&b, 0, 0), ("0x00002010 → outside file".into(), None));
+        // A section at RVA 0x2000 stored at file offset 0x40
+        assert_eq!(rva(&b, 0, 0x40 - 0x2000), ("0x00002010 → 0x00000050".into(), Some(0x50)));
+        assert_eq!(rva(&b, 0x2000, 0), ("0x00002010 → 0x00000010".into(), Some(0x10)));
+        // Below the base is outside the file, not a wrapped-around offset
+        assert_eq!(rva(&b, 0x3000, 0).1, None);
+
+        // Offsets are into the file, and the data may start partway into it
+        let window = OffsetFormat { base: OffsetBase::Hexadecimal, origin: 0x2000 };
+        let row = DataInspector::interpret(Rva32, &b, Radix::Decimal, true, 0x100, window, RowOptions::default());
+        assert_eq!(row.jump_target, Some(0x10));
+    }
+
+    #[test]
+    fn test_window() {
+        let data = vec![0u8; 1000];
+        assert_eq!(DataInspector::window(&data, 0).map(<[u8]>::len), Some(DataInspector::WINDOW_LEN));
+        assert_eq!(DataInspector::window(&data, 990).map(<[u8]>::len), Some(10));
+        assert_eq!(DataInspector::window(&data, 1000), None);
+        assert_eq!(DataInspector::window(&data, 2000), None);
+    }
+}
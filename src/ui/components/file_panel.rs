@@ -1,12 +1,140 @@
 use eframe::egui;
-use std::path::PathBuf;
+use std::collections::BTreeSet;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::fs::File;
-use memmap2::Mmap;
+use memmap2::{Mmap, MmapOptions};
+
+use crate::search::{Concat, Haystack, PartMap};
+use crate::ui::int_parse::IntParser;
+use crate::ui::util::Encoding;
+
+/// Bytes of the open file: a read-only mapping of a large file, or an owned copy.
+/// Either way they are shared with search threads and never written in place.
+#[derive(Clone)]
+pub enum FileData {
+    Mapped(Arc<Mmap>),
+    Buffered(Arc<Vec<u8>>),
+}
+
+impl FileData {
+    /// Largest stretch of a file read into memory instead of being mapped.
+    pub const COPY_LIMIT: u64 = 64 << 20;
+    /// Largest mapping copied into memory on the first edit, since the copy
+    /// holds up the UI until it is done.
+    pub const EDIT_LIMIT: usize = 256 << 20;
+
+    pub fn as_slice(&self) -> &[u8] {
+        match self {
+            FileData::Mapped(m) => m,
+            FileData::Buffered(v) => v,
+        }
+    }
+
+    /// Loads `len` bytes of `file` starting at `start`, which must all be in
+    /// the file. A copy cannot fault if another program truncates the file,
+    /// so only stretches over `COPY_LIMIT` are mapped. An empty one is never
+    /// mapped, since some platforms refuse zero-length mappings.
+    pub fn load(file: &File, start: u64, len: u64) -> std::io::Result<Self> {
+        let len_usize = usize::try_from(len).map_err(std::io::Error::other)?;
+        if len <= Self::COPY_LIMIT {
+            let mut buffer = Vec::with_capacity(len_usize);
+            let mut file = file;
+            file.seek(SeekFrom::Start(start))?;
+            file.take(len).read_to_end(&mut buffer)?;
+            Ok(FileData::Buffered(Arc::new(buffer)))
+        } else {
+            // Safety: the mapping is read-only and only ever read, from this
+            // thread and from searches. Another program changing the file is
+            // outside that guarantee; truncation is caught by `check_truncated`
+            let mmap = unsafe { MmapOptions::new().offset(start).len(len_usize).map(file)? };
+            Ok(FileData::Mapped(Arc::new(mmap)))
+        }
+    }
+
+    /// All of the file at `path`, loaded as by `load`.
+    pub fn open(path: &Path) -> std::io::Result<Self> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len();
+        Self::load(&file, 0, len)
+    }
+}
+
+fn format_size(len: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if len < 1024 {
+        return format!("{} bytes", len);
+    }
+    let mut size = len as f64 / 1024.;
+    let mut unit = 0;
+    while size >= 1024. && unit < UNITS.len() - 1 {
+        size /= 1024.;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Part of a file to load instead of all of it. Offsets into the loaded
+/// bytes are shown relative to the file, starting from `start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileWindow {
+    pub start: u64,
+    /// `None` runs to the end of the file.
+    pub len: Option<u64>,
+}
+
+/// Files opened end to end as one, such as the pieces of a split archive,
+/// in the order they were joined.
+#[derive(Debug, Clone)]
+pub struct FileParts {
+    pub paths: Vec<PathBuf>,
+    pub map: PartMap,
+}
+
+impl FileParts {
+    /// The file holding `offset` in the joined bytes, and the offset within it.
+    pub fn locate(&self, offset: usize) -> Option<(&Path, usize)> {
+        let (index, local) = self.map.locate(offset)?;
+        Some((&self.paths[index], local))
+    }
+}
+
+impl Haystack for FileData {
+    fn as_bytes(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
 
 pub struct FilePanel {
     file_path: Option<PathBuf>,
-    file_data: Option<Arc<Mmap>>,
+    file_data: Option<FileData>,
+    edited: BTreeSet<usize>,
+    dirty: bool,
+    // Window of the open file, `None` when all of it is loaded
+    window: Option<FileWindow>,
+    // Files joined into the loaded bytes, with no `file_path`, or `None`
+    // for a single file
+    parts: Option<FileParts>,
+    // Handle of the mapped file, watched for truncation
+    mapped_file: Option<File>,
+    // Window inputs for the next open
+    window_enabled: bool,
+    window_start_input: String,
+    window_len_input: String,
+    // Bumped whenever the bytes change, so views can cache derived data
+    revision: u64,
+    // Set when Reload reopened the file, until the app picks it up with
+    // `take_reloaded`
+    reloaded: bool,
+    // Last failure, until the app picks it up with `take_error`
+    error: Option<String>,
+}
+
+impl Default for FilePanel {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl FilePanel {
@@ -14,6 +142,17 @@ impl FilePanel {
         Self {
             file_path: None,
             file_data: None,
+            edited: BTreeSet::new(),
+            dirty: false,
+            window: None,
+            parts: None,
+            mapped_file: None,
+            window_enabled: false,
+            window_start_input: String::new(),
+            window_len_input: String::new(),
+            revision: 0,
+            reloaded: false,
+            error: None,
         }
     }
 
@@ -22,58 +161,431 @@ impl FilePanel {
     }
 
     pub fn get_file_data(&self) -> Option<&[u8]> {
-        self.file_data.as_deref().map(|m| {
-            m.as_ref()
-        })
+        self.file_data.as_ref().map(FileData::as_slice)
     }
-    
-    pub fn get_file_data_arc(&self) -> Option<Arc<Mmap>> {
+
+    /// Cheap handle to the current bytes. Later edits copy on write, so a
+    /// search running on this handle keeps seeing the bytes as they are now.
+    pub fn get_file_data_arc(&self) -> Option<FileData> {
         self.file_data.clone()
     }
 
+    pub fn get_edited_offsets(&self) -> &BTreeSet<usize> {
+        &self.edited
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Whether the edited bytes may be dropped: there are no unsaved edits,
+    /// or the user agreed to discard them.
+    pub fn confirm_discard(&self) -> bool {
+        if !self.dirty {
+            return true;
+        }
+        let name = self
+            .file_path
+            .as_ref()
+            .and_then(|p| p.file_name())
+            .map_or_else(|| "the file".into(), |n| n.to_string_lossy());
+        rfd::MessageDialog::new()
+            .set_level(rfd::MessageLevel::Warning)
+            .set_title("Unsaved changes")
+            .set_description(format!("Discard the unsaved changes to {}?", name))
+            .set_buttons(rfd::MessageButtons::YesNo)
+            .show()
+            == rfd::MessageDialogResult::Yes
+    }
+
+    pub fn get_parts(&self) -> Option<&FileParts> {
+        self.parts.as_ref()
+    }
+
+    /// File offset of the first loaded byte.
+    pub fn get_window_start(&self) -> usize {
+        // `open_window` only keeps windows whose offsets fit in `usize`
+        self.window
+            .and_then(|w| usize::try_from(w.start).ok())
+            .unwrap_or(0)
+    }
+
+    pub fn get_revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn take_error(&mut self) -> Option<String> {
+        self.error.take()
+    }
+
+    /// Whether the last file opened by `render` was the same file reloaded.
+    pub fn take_reloaded(&mut self) -> bool {
+        std::mem::take(&mut self.reloaded)
+    }
+
     pub fn clear_file(&mut self) {
         self.file_path = None;
         self.file_data = None;
+        self.window = None;
+        self.parts = None;
+        self.mapped_file = None;
+        self.edited.clear();
+        self.dirty = false;
+        self.revision += 1;
+    }
+
+    /// Overwrites one byte, switching to an in-memory copy of the file on the first edit.
+    /// Handles from `get_file_data_arc` keep the bytes they had: a buffer still
+    /// shared with a search is copied rather than changed under it. Mappings
+    /// over `FileData::EDIT_LIMIT` are not copied, and the edit is refused.
+    pub fn write_byte(&mut self, offset: usize, value: u8) {
+        let Some(data) = &mut self.file_data else {
+            return;
+        };
+        if let FileData::Mapped(mmap) = data {
+            if mmap.len() > FileData::EDIT_LIMIT {
+                self.error = Some(format!(
+                    "Too large to edit in memory, open a window of at most {} MiB to edit",
+                    FileData::EDIT_LIMIT >> 20
+                ));
+                return;
+            }
+            *data = FileData::Buffered(Arc::new(mmap.to_vec()));
+        }
+        let FileData::Buffered(buffer) = data else {
+            unreachable!()
+        };
+        if let Some(byte) = Arc::make_mut(buffer).get_mut(offset) {
+            *byte = value;
+            self.edited.insert(offset);
+            self.dirty = true;
+            self.revision += 1;
+        }
+    }
+
+    /// Swaps the mapping for a copy of what is left of the file if another
+    /// program truncated it, returning whether it did. Reading a mapped page
+    /// past the new end of the file raises SIGBUS, so this has to run before
+    /// anything touches the data; it cannot help a read that races with the
+    /// truncation, or a search thread still scanning the old mapping.
+    pub fn check_truncated(&mut self) -> bool {
+        let (Some(FileData::Mapped(mmap)), Some(file)) = (&self.file_data, &self.mapped_file) else {
+            return false;
+        };
+        let start = self.window.map_or(0, |w| w.start);
+        let Ok(file_len) = file.metadata().map(|m| m.len()) else {
+            return false;
+        };
+        let mapped_len = mmap.len() as u64;
+        if file_len >= start + mapped_len {
+            return false;
+        }
+        let remaining = file_len.saturating_sub(start);
+        let mut copy = Vec::new();
+        let mut file = file;
+        let read = file
+            .seek(SeekFrom::Start(start))
+            .and_then(|_| file.take(remaining).read_to_end(&mut copy));
+        let name = self.file_path.as_deref().map_or("The file".into(), |p| p.display().to_string());
+        self.error = Some(match read {
+            Ok(_) => format!(
+                "{} was truncated by another program, showing the {} still in it",
+                name,
+                format_size(copy.len())
+            ),
+            Err(e) => format!("{} was truncated by another program and could not be read again: {}", name, e),
+        });
+        self.file_data = Some(FileData::Buffered(Arc::new(copy)));
+        self.mapped_file = None;
+        self.revision += 1;
+        true
+    }
+
+    pub fn open_path(&mut self, path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+        self.open_window(path, None)
+    }
+
+    /// Opens `window` of the file at `path`, or all of it for `None`. Only
+    /// the window is loaded; a length past the end of the file is clamped.
+    /// Windows up to `FileData::COPY_LIMIT` are read into memory, larger ones mapped.
+    pub fn open_window(
+        &mut self,
+        path: PathBuf,
+        window: Option<FileWindow>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // Open the file
+        let file = File::open(&path)?;
+        let file_len = file.metadata()?.len();
+        let (start, len) = match window {
+            None => (0, file_len),
+            Some(w) if w.start >= file_len => {
+                return Err(format!("window starts past the end of the file ({} bytes)", file_len).into());
+            }
+            Some(w) => {
+                let rest = file_len - w.start;
+                (w.start, w.len.map_or(rest, |len| len.min(rest)))
+            }
+        };
+        // Offsets into the window are shown as file offsets, which must fit in `usize`
+        if usize::try_from(start + len).is_err() {
+            return Err(format!("window ends past offset 0x{:X}, the largest this build can address", usize::MAX).into());
+        }
+
+        let data = FileData::load(&file, start, len)?;
+        // Kept to watch a mapping for truncation
+        let mapped_file = matches!(data, FileData::Mapped(_)).then_some(file);
+
+        // Update state
+        self.file_path = Some(path);
+        self.file_data = Some(data);
+        self.window = window;
+        self.parts = None;
+        self.mapped_file = mapped_file;
+        self.edited.clear();
+        self.dirty = false;
+        self.revision += 1;
+        Ok(())
+    }
+
+    /// Opens `paths` joined end to end in that order, as one file. The joined
+    /// bytes are a copy, so edits only reach disk through Save As.
+    pub fn open_parts(&mut self, paths: Vec<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+        if paths.is_empty() {
+            return Err("no files to join".into());
+        }
+        let mut parts = Vec::with_capacity(paths.len());
+        for path in &paths {
+            let file = File::open(path)?;
+            // Mapped only until they are copied, and never when empty
+            let part = if file.metadata()?.len() == 0 {
+                FileData::Buffered(Arc::new(Vec::new()))
+            } else {
+                // Safety: read-only and dropped once copied below
+                FileData::Mapped(Arc::new(unsafe { Mmap::map(&file)? }))
+            };
+            parts.push(part);
+        }
+        let (bytes, map) = Concat::new(&parts).into_parts();
+
+        self.file_path = None;
+        self.file_data = Some(FileData::Buffered(Arc::new(bytes)));
+        self.window = None;
+        self.parts = Some(FileParts { paths, map });
+        self.mapped_file = None;
+        self.edited.clear();
+        self.dirty = false;
+        self.revision += 1;
+        Ok(())
+    }
+
+    /// Shows the open dialog for several files, joined in name order, and
+    /// returns whether they were opened.
+    fn open_parts_with_dialog(&mut self) -> bool {
+        let Some(mut paths) = rfd::FileDialog::new().pick_files() else {
+            return false;
+        };
+        // Split archives number their pieces, so name order is part order
+        paths.sort();
+        match self.open_parts(paths) {
+            Ok(()) => true,
+            Err(e) => {
+                self.error = Some(format!("Failed to open parts: {}", e));
+                false
+            }
+        }
+    }
+
+    /// The window typed into the panel, if windowed opening is turned on.
+    fn requested_window(&self) -> Result<Option<FileWindow>, String> {
+        if !self.window_enabled {
+            return Ok(None);
+        }
+        let start = match self.window_start_input.trim() {
+            "" => 0,
+            input => IntParser::parse::<u64>(input)
+                .map_err(|e| format!("invalid window start: {}", e))?,
+        };
+        let len = match self.window_len_input.trim() {
+            "" => None,
+            input => match IntParser::parse::<u64>(input) {
+                Ok(0) => return Err("window length must not be zero".into()),
+                Ok(len) => Some(len),
+                Err(e) => return Err(format!("invalid window length: {}", e)),
+            },
+        };
+        Ok(Some(FileWindow { start, len }))
     }
 
     fn open_file(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let window = self.requested_window()?;
         // Show native file dialog
         if let Some(path) = rfd::FileDialog::new().pick_file() {
-            // Open the file
-            let file = File::open(&path)?;
-            
-            // Create memory-mapped file
-            let mmap = unsafe { Mmap::map(&file)? };
-            
-            // Update state
-            self.file_path = Some(path);
-            self.file_data = Some(Arc::new(mmap));
-        }
-        
+            self.open_window(path, window)?;
+        }
+
         Ok(())
     }
 
+    /// Writes the current bytes to `path` through a temporary file in the
+    /// same directory, so a failed write never leaves a half-written target.
+    /// This also avoids writing through a mapping of the file being replaced.
+    fn save_to(&mut self, path: &Path) -> std::io::Result<()> {
+        let Some(data) = &self.file_data else {
+            return Ok(());
+        };
+        let file_name = path.file_name().unwrap_or(std::ffi::OsStr::new("file"));
+        let tmp_path =
+            path.with_file_name(format!(".{}.rsearch-tmp", file_name.to_string_lossy()));
+        let result = (|| {
+            let mut tmp = File::create(&tmp_path)?;
+            tmp.write_all(data.as_slice())?;
+            // The rename replaces the file, so keep the mode of the one it replaces
+            if let Ok(metadata) = std::fs::metadata(path) {
+                tmp.set_permissions(metadata.permissions())?;
+            }
+            tmp.sync_all()?;
+            std::fs::rename(&tmp_path, path)
+        })();
+        if result.is_err() {
+            let _ = std::fs::remove_file(&tmp_path);
+        }
+        result?;
+
+        // The new file holds only the window's bytes, or all the parts joined
+        self.file_path = Some(path.to_path_buf());
+        self.window = None;
+        self.parts = None;
+        self.edited.clear();
+        self.dirty = false;
+        Ok(())
+    }
+
+    fn save_file(&mut self, save_as: bool) {
+        let path = if save_as || self.file_path.is_none() {
+            let mut dialog = rfd::FileDialog::new();
+            if let Some(current) = &self.file_path {
+                if let Some(dir) = current.parent() {
+                    dialog = dialog.set_directory(dir);
+                }
+                if let Some(name) = current.file_name() {
+                    dialog = dialog.set_file_name(name.to_string_lossy());
+                }
+            }
+            dialog.save_file()
+        } else {
+            self.file_path.clone()
+        };
+        let Some(path) = path else {
+            return;
+        };
+        if let Err(e) = self.save_to(&path) {
+            self.error = Some(format!("Failed to save {}: {}", path.display(), e));
+        }
+    }
+
+    /// Shows the open dialog and returns whether a file was opened.
+    pub fn open_with_dialog(&mut self) -> bool {
+        match self.open_file() {
+            Ok(()) => self.file_data.is_some(),
+            Err(e) => {
+                self.error = Some(format!("Failed to open file: {}", e));
+                false
+            }
+        }
+    }
+
     pub fn render(&mut self, ui: &mut egui::Ui) -> bool {
         let mut file_opened = false;
 
         // Top section - File controls
         ui.horizontal(|ui| {
-            if ui.button("Open File").clicked() {
-                match self.open_file() {
+            if ui.button("Open File").clicked() && self.confirm_discard() {
+                file_opened = self.open_with_dialog();
+            }
+            if ui.button("Open Parts")
+                .on_hover_text("Open several files joined end to end, in name order, as one")
+                .clicked()
+                && self.confirm_discard()
+            {
+                file_opened = self.open_parts_with_dialog();
+            }
+            ui.checkbox(&mut self.window_enabled, "Window")
+                .on_hover_text("Open only part of the file");
+            if self.window_enabled {
+                ui.label("Start:");
+                ui.add(egui::TextEdit::singleline(&mut self.window_start_input).desired_width(90.).hint_text("0"));
+                ui.label("Length:");
+                ui.add(egui::TextEdit::singleline(&mut self.window_len_input).desired_width(90.).hint_text("to end"));
+            }
+
+            let reload = ui.add_enabled(self.file_path.is_some() || self.parts.is_some(), egui::Button::new("Reload"));
+            if reload.clicked() && self.confirm_discard() {
+                let parts = self.parts.as_ref().map(|p| p.paths.clone());
+                let result = match (self.file_path.clone(), parts) {
+                    (Some(path), _) => self.open_window(path.clone(), self.window)
+                        .map_err(|e| format!("Failed to reload {}: {}", path.display(), e)),
+                    (None, Some(paths)) => self.open_parts(paths)
+                        .map_err(|e| format!("Failed to reload parts: {}", e)),
+                    (None, None) => Err("No file to reload".into()),
+                };
+                match result {
                     Ok(()) => {
-                        if self.file_data.is_some() {
-                            file_opened = true;
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("Failed to open file: {}", e);
-                        // Optionally show error in UI
+                        file_opened = true;
+                        self.reloaded = true;
                     }
+                    Err(e) => self.error = Some(e),
                 }
             }
-            
+
+            let loaded = self.file_data.is_some();
+            let save = ui
+                .add_enabled(loaded && self.dirty && self.window.is_none() && self.parts.is_none(), egui::Button::new("Save"))
+                .on_disabled_hover_text(if self.window.is_some() {
+                    "Only part of the file is open, use Save As to write it to a new file"
+                } else if self.parts.is_some() {
+                    "Several files are joined, use Save As to write them to a new file"
+                } else {
+                    "No changes to save"
+                });
+            if save.clicked() {
+                self.save_file(false);
+            }
+            if ui.add_enabled(loaded, egui::Button::new("Save As")).clicked() {
+                self.save_file(true);
+            }
+
             if let Some(path) = &self.file_path {
                 ui.label(format!("File: {}", path.file_name().unwrap_or(std::ffi::OsStr::new("??")).to_string_lossy()));
+                if let Some(data) = &self.file_data {
+                    let storage = match data {
+                        FileData::Mapped(_) => "memory-mapped",
+                        FileData::Buffered(_) => "in memory",
+                    };
+                    ui.label(format!("{}, {}", format_size(data.as_slice().len()), storage));
+                    if let Some(window) = self.window {
+                        let end = window.start + data.as_slice().len() as u64;
+                        ui.label(format!("window 0x{:X}..0x{:X}", window.start, end));
+                    }
+                    if self.window.is_none()
+                        && let Some(encoding) = Encoding::detect_bom(data.as_slice())
+                    {
+                        ui.label(format!("{} BOM", encoding))
+                            .on_hover_text("The file starts with a byte order mark");
+                    }
+                }
+                if self.dirty {
+                    ui.label(egui::RichText::new("(modified)").italics());
+                }
+            } else if let Some(parts) = &self.parts {
+                let names: Vec<String> = parts.paths.iter().map(|p| p.display().to_string()).collect();
+                ui.label(format!("{} parts joined", parts.paths.len()))
+                    .on_hover_text(names.join("\n"));
+                if let Some(data) = &self.file_data {
+                    ui.label(format!("{}, in memory", format_size(data.as_slice().len())));
+                }
+                if self.dirty {
+                    ui.label(egui::RichText::new("(modified)").italics());
+                }
             } else {
                 ui.label("No file loaded");
             }
@@ -82,3 +594,41 @@ impl FilePanel {
         file_opened
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempPath;
+
+    #[test]
+    fn test_open_parts() {
+        let dir = TempPath::new("parts");
+        std::fs::create_dir_all(&dir).unwrap();
+        let paths: Vec<PathBuf> = ["a.001", "a.002", "a.003"].iter().map(|n| dir.join(n)).collect();
+        std::fs::write(&paths[0], b"head").unwrap();
+        std::fs::write(&paths[1], b"").unwrap();
+        std::fs::write(&paths[2], b"tail").unwrap();
+
+        let mut panel = FilePanel::new();
+        panel.open_parts(paths.clone()).unwrap();
+        assert_eq!(panel.get_file_data(), Some(b"headtail".as_slice()));
+        assert_eq!(panel.get_file_path(), &None);
+        let parts = panel.get_parts().unwrap();
+        assert_eq!(parts.locate(5), Some((paths[2].as_path(), 1)));
+        assert!(FilePanel::new().open_parts(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_edit_keeps_shared_bytes() {
+        let path = TempPath::new("edit");
+        std::fs::write(&path, b"abcd").unwrap();
+        let mut panel = FilePanel::new();
+        panel.open_path(path.to_path_buf()).unwrap();
+
+        // As a search would hold it
+        let handle = panel.get_file_data_arc().unwrap();
+        panel.write_byte(1, b'X');
+        assert_eq!(panel.get_file_data(), Some(&b"aXcd"[..]));
+        assert_eq!(handle.as_slice(), b"abcd");
+    }
+}
@@ -1,29 +1,250 @@
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+use std::path::Path;
+
 use eframe::egui::{self, Response};
 use egui_extras::{Column, TableBuilder};
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
 
-use crate::ui::util::Selection;
+use crate::ui::util::{
+    Bookmark, OffsetFormat, SearchResult, Selection, WIN1252_80_9F, copy_offset, format_delta,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum DragStatus {
     Idle,
     Bytes(usize),
-    ASCII(usize),
+    Ascii(usize),
 }
 
 impl DragStatus {
     fn type_matches(&self, other: Self) -> bool {
-        match (self, other) {
-            (DragStatus::Idle, DragStatus::Idle) => true,
-            (DragStatus::Bytes(_), DragStatus::Bytes(_)) => true,
-            (DragStatus::ASCII(_), DragStatus::ASCII(_)) => true,
-            _ => false,
+        matches!(
+            (self, other),
+            (DragStatus::Idle, DragStatus::Idle)
+                | (DragStatus::Bytes(_), DragStatus::Bytes(_))
+                | (DragStatus::Ascii(_), DragStatus::Ascii(_))
+        )
+    }
+}
+
+/// Background tint of the byte cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+pub enum ColorMode {
+    Plain,
+    /// Tint each byte by its own value.
+    ByteValue,
+    /// Tint each line by the Shannon entropy of the bytes around it.
+    Entropy,
+}
+
+impl std::fmt::Display for ColorMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColorMode::Plain => write!(f, "Plain"),
+            ColorMode::ByteValue => write!(f, "Byte Value"),
+            ColorMode::Entropy => write!(f, "Entropy"),
         }
     }
 }
 
+/// How bytes are drawn in the text column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+pub enum TextEncoding {
+    Ascii,
+    Windows1252,
+    Cp437,
+}
+
+impl std::fmt::Display for TextEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TextEncoding::Ascii => write!(f, "ASCII"),
+            TextEncoding::Windows1252 => write!(f, "Win-1252"),
+            TextEncoding::Cp437 => write!(f, "CP437"),
+        }
+    }
+}
+
+/// Code page 437 glyphs, as drawn by the IBM PC for every byte value
+/// (0x00 is shown as `.` so zero runs stay visible).
+#[rustfmt::skip]
+const CP437: [char; 256] = [
+    '.', '☺', '☻', '♥', '♦', '♣', '♠', '•', '◘', '○', '◙', '♂', '♀', '♪', '♫', '☼',
+    '►', '◄', '↕', '‼', '¶', '§', '▬', '↨', '↑', '↓', '→', '←', '∟', '↔', '▲', '▼',
+    ' ', '!', '"', '#', '$', '%', '&', '\'', '(', ')', '*', '+', ',', '-', '.', '/',
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', ':', ';', '<', '=', '>', '?',
+    '@', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O',
+    'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '[', '\\', ']', '^', '_',
+    '`', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o',
+    'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', '{', '|', '}', '~', '⌂',
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å',
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ',
+    'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»',
+    '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐',
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧',
+    '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀',
+    'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩',
+    '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{a0}',
+];
+
+impl TextEncoding {
+    /// Glyph for `byte`, or `.` where the encoding has nothing printable.
+    fn display_char(self, byte: u8) -> char {
+        match self {
+            TextEncoding::Ascii if byte.is_ascii_graphic() => byte as char,
+            TextEncoding::Ascii => '.',
+            TextEncoding::Windows1252 => match byte {
+                0x20..=0x7E | 0xA1..=0xFF => char::from(byte),
+                0x80..=0x9F => WIN1252_80_9F[(byte - 0x80) as usize].unwrap_or('.'),
+                _ => '.',
+            },
+            TextEncoding::Cp437 => CP437[byte as usize],
+        }
+    }
+}
+
+/// How the selection is filled. Shared by every tab and kept between runs.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SelectionStyle {
+    /// Fill color, `None` for the theme's selection color.
+    pub color: Option<[u8; 3]>,
+    /// Below 1.0 the match, diff and heatmap tints show through the selection.
+    pub opacity: f32,
+}
+
+impl Default for SelectionStyle {
+    fn default() -> Self {
+        Self {
+            color: None,
+            opacity: 1.0,
+        }
+    }
+}
+
+impl SelectionStyle {
+    // Fainter than this and the selection is easy to lose
+    const MIN_OPACITY: f32 = 0.2;
+
+    fn is_translucent(&self) -> bool {
+        self.opacity < 1.0
+    }
+
+    fn fill(&self, visuals: &egui::Visuals) -> egui::Color32 {
+        let [r, g, b] = self.color.unwrap_or_else(|| {
+            let [r, g, b, _] = visuals.selection.bg_fill.to_srgba_unmultiplied();
+            [r, g, b]
+        });
+        let alpha = self.opacity.clamp(Self::MIN_OPACITY, 1.0) * 255.0;
+        egui::Color32::from_rgba_unmultiplied(r, g, b, alpha.round() as u8)
+    }
+
+    /// Color and opacity controls, for a settings popup.
+    pub fn render(&mut self, ui: &mut egui::Ui) {
+        let mut theme_color = self.color.is_none();
+        if ui
+            .checkbox(&mut theme_color, "Theme color")
+            .on_hover_text("Use the selection color of the light or dark theme")
+            .changed()
+        {
+            self.color = if theme_color {
+                None
+            } else {
+                let [r, g, b, _] = ui.visuals().selection.bg_fill.to_srgba_unmultiplied();
+                Some([r, g, b])
+            };
+        }
+        if let Some(color) = &mut self.color {
+            ui.horizontal(|ui| {
+                ui.label("Color:");
+                ui.color_edit_button_srgb(color);
+            });
+        }
+        ui.add(
+            egui::Slider::new(&mut self.opacity, Self::MIN_OPACITY..=1.0)
+                .text("Opacity")
+                .fixed_decimals(2),
+        )
+        .on_hover_text("Lower it to see search matches and differences under the selection");
+        if ui.button("Reset").clicked() {
+            *self = Self::default();
+        }
+    }
+}
+
+/// Ranges painted underneath the selection, e.g. every hit of the current search.
+#[derive(Clone, Copy)]
+pub struct Highlights<'a> {
+    /// Search results in ascending offset order, as streamed by `AsyncSearch`.
+    pub matches: &'a [SearchResult],
+    pub bookmarks: &'a [Bookmark],
+    /// Offsets overwritten in edit mode.
+    pub edited: &'a BTreeSet<usize>,
+    /// The selection is a search match jumped to rather than a manual pick;
+    /// it is outlined as a whole and scrolled to the middle of the view.
+    pub selection_is_match: bool,
+    /// Runs that differ from the file being compared with, in ascending
+    /// offset order, painted instead of `matches`.
+    pub diffs: &'a [SearchResult],
+    pub selection_style: SelectionStyle,
+    /// Offset the data inspector decodes from, marked with a bar on its left
+    /// edge so it stands out in a longer selection.
+    pub inspector_anchor: Option<usize>,
+}
+
+impl Highlights<'_> {
+    fn is_match(&self, offset: usize) -> bool {
+        // Only the last match starting at or before `offset` is checked. That
+        // is exact for fixed needles, whose matches all share one length.
+        let idx = self.matches.partition_point(|r| r.offset <= offset);
+        idx > 0 && offset < self.matches[idx - 1].offset + self.matches[idx - 1].len
+    }
+
+    fn is_diff(&self, offset: usize) -> bool {
+        // Runs never overlap, so only the last one starting at or before
+        // `offset` can cover it.
+        let idx = self.diffs.partition_point(|r| r.offset <= offset);
+        idx > 0 && offset < self.diffs[idx - 1].offset + self.diffs[idx - 1].len
+    }
+
+    fn is_bookmarked(&self, offset: usize) -> bool {
+        self.bookmarks.iter().any(|b| b.offset == offset)
+    }
+}
+
+/// A byte typed over in edit mode, for the owner of the data to apply.
+#[derive(Debug, Clone, Copy)]
+pub struct ByteEdit {
+    pub offset: usize,
+    pub value: u8,
+}
+
 pub struct HexViewer {
     drag_status: DragStatus,
     drag_counter: usize,
+    color_mode: ColorMode,
+    edit_mode: bool,
+    // Offset and high nibble typed so far for the byte at the selection start
+    pending_nibble: Option<(usize, u8)>,
+    // Extra gap after every `GROUP_SIZE` byte columns
+    grouping: bool,
+    text_encoding: TextEncoding,
+    // Last failed export, until the app picks it up with `take_error`
+    error: Option<String>,
+    // Where the table was scrolled to and laid out last frame. When the
+    // layout changes the offset is put back, so a reflow never loses the place.
+    scroll_offset: f32,
+    table_rect: Option<egui::Rect>,
+    // First line in view last frame, and a line to scroll to the top next frame
+    top_line: usize,
+    pending_top_line: Option<usize>,
+    // Start of the last committed selection, and of the one before it, which
+    // the delta readout measures from
+    selection_start: Option<usize>,
+    delta_anchor: Option<usize>,
 }
 
 impl HexViewer {
@@ -31,9 +252,21 @@ impl HexViewer {
     const BYTE_COL_WIDTH: f32 = 14.;
     const ADDRESS_COL_MIN_WIDTH: f32 = 70.;
     const DEFAULT_SPACING: f32 = 8.;
-    const BYTE_COLS_MIN_WIDTH: f32 =
-        (Self::BYTE_COL_WIDTH + Self::DEFAULT_SPACING) * Self::BPL as f32;
+    const GROUP_SIZE: usize = 8;
+    const GROUP_GAP: f32 = 6.;
+    const BYTE_COLS_MIN_WIDTH: f32 = (Self::BYTE_COL_WIDTH + Self::DEFAULT_SPACING)
+        * Self::BPL as f32
+        + (Self::GROUP_GAP + Self::DEFAULT_SPACING);
     const ASCII_COL_MIN_WIDTH: f32 = 120.;
+    const MATCH_COLOR: egui::Color32 = egui::Color32::from_rgba_premultiplied(25, 38, 64, 64);
+    const BOOKMARK_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 170, 40);
+    const EDITED_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 90, 90);
+    const DIFF_COLOR: egui::Color32 = egui::Color32::from_rgba_premultiplied(80, 20, 20, 64);
+    const ANCHOR_COLOR: egui::Color32 = egui::Color32::from_rgb(60, 200, 120);
+    // Bytes on either side of a line that contribute to its entropy.
+    const ENTROPY_CONTEXT: usize = 24;
+    // Largest selection whose dump is put on the clipboard
+    const CLIPBOARD_DUMP_LIMIT: usize = 1 << 20;
     pub const WIDGET_MIN_WIDTH: f32 = Self::DEFAULT_SPACING           // Margin
       + Self::DEFAULT_SPACING           // Padding
       + Self::ADDRESS_COL_MIN_WIDTH
@@ -45,11 +278,143 @@ impl HexViewer {
       + Self::DEFAULT_SPACING;
 }
 
+impl Default for HexViewer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl HexViewer {
     pub fn new() -> Self {
         Self {
             drag_status: DragStatus::Idle,
             drag_counter: 0,
+            color_mode: ColorMode::Plain,
+            edit_mode: false,
+            pending_nibble: None,
+            grouping: true,
+            text_encoding: TextEncoding::Ascii,
+            error: None,
+            scroll_offset: 0.0,
+            table_rect: None,
+            top_line: 0,
+            pending_top_line: None,
+            selection_start: None,
+            delta_anchor: None,
+        }
+    }
+
+    /// Offset of the first line in view.
+    pub fn top_offset(&self) -> usize {
+        self.top_line * Self::BPL
+    }
+
+    /// Scrolls the line holding `offset` to the top of the view on the next render.
+    pub fn scroll_to_offset(&mut self, offset: usize) {
+        self.pending_top_line = Some(offset / Self::BPL);
+    }
+
+    /// Line at `percent` of the way through `len` bytes, clamped to the last line.
+    fn line_at_percent(percent: f64, len: usize) -> usize {
+        let lines = len.div_ceil(Self::BPL);
+        let line = (percent / 100.0).clamp(0.0, 1.0) * lines as f64;
+        (line as usize).min(lines.saturating_sub(1))
+    }
+
+    /// Slider that scrolls to a fraction of the file, for coarse moves
+    /// through files too large to scroll. Follows the scroll position otherwise.
+    fn render_position_slider(&mut self, ui: &mut egui::Ui, len: usize, offset_base: OffsetFormat) {
+        ui.horizontal(|ui| {
+            ui.label("Position:");
+            let lines = len.div_ceil(Self::BPL).max(1);
+            let mut percent = (self.top_line as f64 / lines as f64 * 100.0).min(100.0);
+            // Leave room for the offset label
+            ui.spacing_mut().slider_width = (ui.available_width() - 180.0).max(100.0);
+            let slider = egui::Slider::new(&mut percent, 0.0..=100.0)
+                .suffix("%")
+                .fixed_decimals(1);
+            if ui.add_enabled(len > 0, slider).changed() {
+                self.pending_top_line = Some(Self::line_at_percent(percent, len));
+            }
+            let line = self.pending_top_line.unwrap_or(self.top_line);
+            ui.monospace(offset_base.format(line * Self::BPL))
+                .on_hover_text("Offset of the first line in view");
+        });
+    }
+
+    /// Forgets the previous selection, e.g. when another file is opened.
+    pub fn clear_delta_anchor(&mut self) {
+        self.selection_start = None;
+        self.delta_anchor = None;
+    }
+
+    /// Moves the delta anchor to the previous selection start once a new
+    /// selection is committed, not while the mouse is still dragging it out.
+    fn track_delta_anchor(&mut self, ui: &egui::Ui, selection: Option<Selection>) {
+        let Some(start) = selection.map(|s| s.lower()) else {
+            return;
+        };
+        if self.selection_start != Some(start) && !ui.input(|i| i.pointer.any_down()) {
+            self.delta_anchor = self.selection_start;
+            self.selection_start = Some(start);
+        }
+    }
+
+    /// Signed distance from `anchor` to `offset`, as `+0x20 (+32)`.
+    pub fn take_error(&mut self) -> Option<String> {
+        self.error.take()
+    }
+
+    /// Writes `data` as an `xxd`-style dump: address, hex in pairs of bytes
+    /// and the text column, `BPL` bytes per line. Addresses start at `start`.
+    pub fn write_hex_dump(
+        out: &mut impl Write,
+        data: &[u8],
+        start: usize,
+        encoding: TextEncoding,
+    ) -> io::Result<()> {
+        for (i, line) in data.chunks(Self::BPL).enumerate() {
+            write!(out, "{:08x}:", start + i * Self::BPL)?;
+            for j in 0..Self::BPL {
+                if j % 2 == 0 {
+                    write!(out, " ")?;
+                }
+                match line.get(j) {
+                    Some(b) => write!(out, "{:02x}", b)?,
+                    None => write!(out, "  ")?,
+                }
+            }
+            let text: String = line.iter().map(|&b| encoding.display_char(b)).collect();
+            writeln!(out, "  {}", text)?;
+        }
+        Ok(())
+    }
+
+    /// Copies the dump of `data` to the clipboard, refusing selections too
+    /// large to hold as one string.
+    fn copy_hex_dump(&mut self, ctx: &egui::Context, data: &[u8], start: usize) {
+        if data.len() > Self::CLIPBOARD_DUMP_LIMIT {
+            self.error = Some("Selection is too large to copy, export it to a file instead".into());
+            return;
+        }
+        let mut dump = Vec::new();
+        // Writing into a Vec cannot fail
+        let _ = Self::write_hex_dump(&mut dump, data, start, self.text_encoding);
+        ctx.copy_text(String::from_utf8_lossy(&dump).into_owned());
+    }
+
+    /// Streams the dump of `data` to a file picked by the user.
+    fn export_hex_dump(&mut self, data: &[u8], start: usize) {
+        let Some(path) = rfd::FileDialog::new().set_file_name("dump.txt").save_file() else {
+            return;
+        };
+        let write = |path: &Path| -> io::Result<()> {
+            let mut out = io::BufWriter::new(std::fs::File::create(path)?);
+            Self::write_hex_dump(&mut out, data, start, self.text_encoding)?;
+            out.flush()
+        };
+        if let Err(e) = write(&path) {
+            self.error = Some(format!("Failed to export {}: {}", path.display(), e));
         }
     }
 
@@ -64,18 +429,18 @@ impl HexViewer {
             DragStatus::Idle => {
                 return;
             }
-            DragStatus::ASCII(offset) => offset,
+            DragStatus::Ascii(offset) => offset,
             DragStatus::Bytes(offset) => offset,
         };
         if resp.clicked() {
-            println!("Clicked");
+            log::trace!("Clicked");
             *selection = Some(Selection::new(off));
             self.drag_status = DragStatus::Idle;
         }
 
         // Handle drag start
         if resp.drag_started() {
-            println!("Drag Started {:?}", status);
+            log::trace!("Drag Started {:?}", status);
             self.drag_status = status;
             *selection = Some(Selection::new(off));
             self.drag_counter = 0;
@@ -83,9 +448,11 @@ impl HexViewer {
 
         // Handle drag
         if self.drag_status.type_matches(status) && resp.contains_pointer() {
-            println!(
+            log::trace!(
                 "Dragged {:?} to {:?} {}",
-                self.drag_status, status, self.drag_counter
+                self.drag_status,
+                status,
+                self.drag_counter
             );
             if let Some(sel) = selection {
                 sel.update_end(off);
@@ -95,61 +462,312 @@ impl HexViewer {
 
         // Handle drag released - check if we were dragging and now stopped
         if self.drag_status == status && !resp.dragged() {
-            println!("Drag Released {:?}", status);
+            log::trace!("Drag Released {:?}", status);
             self.drag_status = DragStatus::Idle;
             self.drag_counter = 0;
         }
     }
 
+    /// Strokes the sides of the cell at `off`, in column `col`, that lie on
+    /// the border of `sel`, so the cells together outline the whole range.
+    fn paint_range_outline(
+        ui: &egui::Ui,
+        rect: egui::Rect,
+        off: usize,
+        col: usize,
+        sel: Selection,
+    ) {
+        let stroke = egui::Stroke::new(1.5, ui.visuals().selection.stroke.color);
+        let painter = ui.painter();
+        if off < sel.lower() + Self::BPL {
+            painter.hline(rect.x_range(), rect.top(), stroke);
+        }
+        if off + Self::BPL > sel.upper() {
+            painter.hline(rect.x_range(), rect.bottom(), stroke);
+        }
+        if off == sel.lower() || col == 0 {
+            painter.vline(rect.left(), rect.y_range(), stroke);
+        }
+        if off == sel.upper() || col == Self::BPL - 1 {
+            painter.vline(rect.right(), rect.y_range(), stroke);
+        }
+    }
+
+    /// Marks the byte the data inspector reads from with a bar down its left
+    /// edge, inside the cell so the match outline stays visible.
+    fn paint_anchor(ui: &egui::Ui, rect: egui::Rect) {
+        let x = rect.left() + 1.5;
+        ui.painter().vline(
+            x,
+            rect.y_range(),
+            egui::Stroke::new(2.5, Self::ANCHOR_COLOR),
+        );
+    }
+
+    /// Maps `t` in `[0, 1]` onto a translucent blue-to-red ramp.
+    fn heat_color(t: f32) -> egui::Color32 {
+        let t = t.clamp(0., 1.);
+        egui::Color32::from_rgba_unmultiplied(
+            (40. + 215. * t) as u8,
+            (90. * (1. - t)) as u8,
+            (255. * (1. - t)) as u8,
+            (25. + 70. * t) as u8,
+        )
+    }
+
+    /// Shannon entropy of `data[start..end]` widened by `ENTROPY_CONTEXT`,
+    /// normalized to `[0, 1]` by the best a window of that size can reach.
+    fn window_entropy(data: &[u8], start: usize, end: usize) -> f32 {
+        let lo = start.saturating_sub(Self::ENTROPY_CONTEXT);
+        let hi = (end + Self::ENTROPY_CONTEXT).min(data.len());
+        let window = &data[lo..hi];
+        if window.len() < 2 {
+            return 0.;
+        }
+        let mut counts = [0usize; 256];
+        for &b in window {
+            counts[b as usize] += 1;
+        }
+        let n = window.len() as f32;
+        let entropy: f32 = counts
+            .iter()
+            .filter(|&&c| c > 0)
+            .map(|&c| {
+                let p = c as f32 / n;
+                -p * p.log2()
+            })
+            .sum();
+        entropy / n.min(256.).log2()
+    }
+
+    /// Whether the first digit of a byte has been typed but not the second.
+    pub fn has_pending_edit(&self) -> bool {
+        self.pending_nibble.is_some()
+    }
+
+    /// Turns hex digits typed in edit mode into overwrites of the byte at the
+    /// selection start; the cursor advances after each completed byte.
+    fn handle_edit_input(
+        &mut self,
+        ui: &egui::Ui,
+        data_len: usize,
+        selection: &mut Option<Selection>,
+    ) -> Option<ByteEdit> {
+        let Some(sel) = selection else {
+            self.pending_nibble = None;
+            return None;
+        };
+        if !self.edit_mode || ui.ctx().wants_keyboard_input() {
+            self.pending_nibble = None;
+            return None;
+        }
+        if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.pending_nibble = None;
+        }
+        let typed: String = ui.input(|i| {
+            i.events
+                .iter()
+                .filter_map(|e| match e {
+                    egui::Event::Text(t) => Some(t.clone()),
+                    _ => None,
+                })
+                .collect()
+        });
+        let offset = sel.lower();
+        // A click elsewhere abandons the half-typed byte
+        self.pending_nibble = self.pending_nibble.filter(|&(at, _)| at == offset);
+        let mut edit = None;
+        for digit in typed.chars().filter_map(|c| c.to_digit(16)) {
+            let digit = digit as u8;
+            match self.pending_nibble.take() {
+                None => self.pending_nibble = Some((offset, digit)),
+                Some((_, high)) => {
+                    edit = Some(ByteEdit {
+                        offset,
+                        value: (high << 4) | digit,
+                    });
+                    if offset + 1 < data_len {
+                        *sel = Selection::new(offset + 1);
+                    }
+                    // One byte per frame; the rest would be written against stale data
+                    break;
+                }
+            }
+        }
+        edit
+    }
+
     pub fn render(
         &mut self,
         ui: &mut egui::Ui,
         file_data: Option<&[u8]>,
         selection: &mut Option<Selection>,
         selection_changed: bool,
-    ) {
+        highlights: Highlights,
+        offset_base: OffsetFormat,
+    ) -> Option<ByteEdit> {
         // let mut rendered_lines = std::collections::BTreeSet::new();
+        let mut edit = None;
         ui.group(|ui| {
             let data = file_data.unwrap_or(&[]);
-            ui.label(format!(
-                "Hex Viewer | Size = {} ({:x}) bytes",
-                data.len(),
-                data.len()
-            ));
-            let lines = (data.len() + Self::BPL - 1) / Self::BPL;
+            edit = self.handle_edit_input(ui, data.len(), selection);
+            let pending = self.pending_nibble;
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "Hex Viewer | Size = {} ({:x}) bytes",
+                    data.len(),
+                    data.len()
+                ));
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    egui::ComboBox::from_id_salt("HexViewer.ColorMode")
+                        .selected_text(format!("{}", self.color_mode))
+                        .show_ui(ui, |ui| {
+                            for mode in ColorMode::iter() {
+                                ui.selectable_value(
+                                    &mut self.color_mode,
+                                    mode,
+                                    format!("{}", mode),
+                                );
+                            }
+                        });
+                    ui.label("Colors:");
+                    egui::ComboBox::from_id_salt("HexViewer.TextEncoding")
+                        .selected_text(format!("{}", self.text_encoding))
+                        .show_ui(ui, |ui| {
+                            for encoding in TextEncoding::iter() {
+                                ui.selectable_value(
+                                    &mut self.text_encoding,
+                                    encoding,
+                                    format!("{}", encoding),
+                                );
+                            }
+                        });
+                    ui.label("Text:");
+                    ui.checkbox(&mut self.edit_mode, "Edit")
+                        .on_hover_text("Type hex digits to overwrite the selected byte");
+                    ui.checkbox(&mut self.grouping, "Group")
+                        .on_hover_text("Leave a gap after every 8 bytes");
+                });
+            });
+            self.track_delta_anchor(ui, *selection);
+            ui.horizontal(|ui| {
+                let origin = offset_base.origin;
+                let label = ui.label(match selection {
+                    Some(sel) => {
+                        let mut label = format!(
+                            "Selection: 0x{:X}..=0x{:X} ({}..={}) | {} (0x{:X}) bytes",
+                            origin + sel.lower(),
+                            origin + sel.upper(),
+                            origin + sel.lower(),
+                            origin + sel.upper(),
+                            sel.byte_count(),
+                            sel.byte_count()
+                        );
+                        if let Some(anchor) = self.delta_anchor {
+                            label += &format!(" | Δ {}", format_delta(anchor, sel.lower()));
+                        }
+                        label
+                    }
+                    None => "Selection: None".into(),
+                });
+                if let Some(anchor) = self.delta_anchor {
+                    label.on_hover_text(format!(
+                        "Δ is measured from the previous selection start, 0x{:X}",
+                        origin + anchor
+                    ));
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    let selected = selection
+                        .filter(|sel| sel.upper() < data.len())
+                        .map(|sel| (origin + sel.lower(), &data[sel.lower()..=sel.upper()]));
+                    ui.add_enabled_ui(selected.is_some(), |ui| {
+                        if ui.button("Export dump...").clicked()
+                            && let Some((start, bytes)) = selected
+                        {
+                            self.export_hex_dump(bytes, start);
+                        }
+                        if ui
+                            .button("Copy dump")
+                            .on_hover_text("Copy the selection as an xxd-style hex dump")
+                            .clicked()
+                            && let Some((start, bytes)) = selected
+                        {
+                            self.copy_hex_dump(ui.ctx(), bytes, start);
+                        }
+                    });
+                    if ui
+                        .add_enabled(!data.is_empty(), egui::Button::new("Select all"))
+                        .on_hover_text("Ctrl+A")
+                        .clicked()
+                    {
+                        *selection = Selection::whole(data.len());
+                    }
+                });
+            });
+            self.render_position_slider(ui, data.len(), offset_base);
+            let lines = data.len().div_ceil(Self::BPL);
 
             let available_width = ui.available_width();
-            let bytes_width =
-                Self::BPL as f32 * (Self::BYTE_COL_WIDTH + ui.spacing().item_spacing.x);
+            // Byte column `i` is preceded by an empty gap column when this holds
+            let grouping = self.grouping;
+            let gap_before = |i: usize| grouping && i != 0 && i.is_multiple_of(Self::GROUP_SIZE);
+            let gaps = (0..Self::BPL).filter(|&i| gap_before(i)).count();
+            let spacing = ui.spacing().item_spacing.x;
+            let row_pitch = 18.0 + ui.spacing().item_spacing.y;
+            let bytes_width = Self::BPL as f32 * (Self::BYTE_COL_WIDTH + spacing)
+                + gaps as f32 * (Self::GROUP_GAP + spacing);
             let remain_width = available_width - bytes_width;
             let address_width = remain_width * Self::ADDRESS_COL_MIN_WIDTH
                 / (Self::ADDRESS_COL_MIN_WIDTH + Self::ASCII_COL_MIN_WIDTH);
+            let table_rect = ui.available_rect_before_wrap();
+            let selection_fill = highlights.selection_style.fill(ui.visuals());
+            let translucent = highlights.selection_style.is_translucent();
             let mut table = TableBuilder::new(ui)
                 .striped(false)
-                .column(Column::exact(address_width)) // Address
-                .columns(Column::exact(Self::BYTE_COL_WIDTH), Self::BPL) // 16 columns for bytes
-                .column(Column::remainder().at_least(Self::ASCII_COL_MIN_WIDTH)); // ASCII
-            if selection_changed {
-                if let Some(sel) = selection {
+                .column(Column::exact(address_width)); // Address
+            for i in 0..Self::BPL {
+                if gap_before(i) {
+                    table = table.column(Column::exact(Self::GROUP_GAP));
+                }
+                table = table.column(Column::exact(Self::BYTE_COL_WIDTH));
+            }
+            let mut table = table.column(Column::remainder().at_least(Self::ASCII_COL_MIN_WIDTH)); // ASCII
+            if let Some(line) = self.pending_top_line.take() {
+                table = table.scroll_to_row(line, Some(egui::Align::TOP));
+            } else if selection_changed && let Some(sel) = selection {
+                if highlights.selection_is_match {
+                    let row = (sel.lower() + sel.upper()) / 2 / Self::BPL;
+                    table = table.scroll_to_row(row, Some(egui::Align::Center));
+                } else {
                     let row = sel.lower() / Self::BPL;
                     table = table.scroll_to_row(row, None);
                 }
+            } else if self.table_rect.is_some_and(|rect| rect != table_rect) {
+                table = table.vertical_scroll_offset(self.scroll_offset);
             }
-            table
+            self.table_rect = Some(table_rect);
+            let match_selection = selection.filter(|_| highlights.selection_is_match);
+            let output = table
                 .header(20.0, |mut header| {
                     header.col(|ui| {
                         ui.monospace("Address");
                     });
                     for i in 0..Self::BPL {
+                        if gap_before(i) {
+                            header.col(|_| {});
+                        }
                         header.col(|ui| {
                             ui.monospace(format!("{:02X}", i));
                         });
                     }
                     header.col(|ui| {
-                        ui.monospace("ASCII");
+                        ui.monospace(format!("{}", self.text_encoding));
                     });
                 })
                 .body(|body| {
+                    // `rows` is virtualized: the closure only runs for the lines
+                    // scrolled into view, so the cost per frame does not grow with
+                    // the file size
                     body.rows(18.0, lines + 20, |mut row| {
                         let line = row.index();
                         // render extra lines for extra scrollable space
@@ -159,14 +777,30 @@ impl HexViewer {
                         }
                         let start = line * Self::BPL;
                         let end = (start + Self::BPL).min(data.len());
+                        let line_heat = match self.color_mode {
+                            ColorMode::Entropy => Some(Self::window_entropy(data, start, end)),
+                            _ => None,
+                        };
                         row.col(|ui| {
-                            ui.monospace(format!("{:08X}", start));
+                            ui.monospace(offset_base.format_column(start))
+                                .context_menu(|ui| copy_offset(ui, start, offset_base));
                         });
                         for i in 0..Self::BPL {
+                            if gap_before(i) {
+                                row.col(|_| {});
+                            }
                             row.col(|ui: &mut egui::Ui| {
                                 if start + i < data.len() {
                                     let off = start + i;
-                                    let text = format!("{:02X}", data[off]);
+                                    let text = match pending {
+                                        Some((at, nibble)) if at == off => format!("{:X}_", nibble),
+                                        _ => format!("{:02X}", data[off]),
+                                    };
+                                    let text_color = if highlights.edited.contains(&off) {
+                                        Self::EDITED_COLOR
+                                    } else {
+                                        ui.visuals().text_color()
+                                    };
 
                                     // Create a clickable area without text selection
                                     let (rect, resp) = ui.allocate_exact_size(
@@ -174,38 +808,85 @@ impl HexViewer {
                                         egui::Sense::click_and_drag(),
                                     );
 
+                                    // Check if this byte is in selection range
+                                    let is_selected = selection
+                                        .as_ref()
+                                        .map(|sel| sel.contains(off))
+                                        .unwrap_or(false);
+                                    // Tints are only hidden under an opaque selection
+                                    let tinted = !is_selected || translucent;
+
+                                    // Heatmap tint, underneath everything else
+                                    let heat = match self.color_mode {
+                                        ColorMode::Plain => None,
+                                        ColorMode::ByteValue => Some(data[off] as f32 / 255.),
+                                        ColorMode::Entropy => line_heat,
+                                    };
+                                    if let Some(t) = heat
+                                        && tinted
+                                    {
+                                        ui.painter().rect_filled(
+                                            rect.expand2(egui::vec2(1.0, 1.0)),
+                                            2.0,
+                                            Self::heat_color(t),
+                                        );
+                                    }
+
+                                    // Comparison and search match highlighting, underneath the text
+                                    if tinted && highlights.is_diff(off) {
+                                        ui.painter().rect_filled(
+                                            rect.expand2(egui::vec2(1.0, 1.0)),
+                                            2.0,
+                                            Self::DIFF_COLOR,
+                                        );
+                                    }
+                                    if tinted && highlights.is_match(off) {
+                                        ui.painter().rect_filled(
+                                            rect.expand2(egui::vec2(1.0, 1.0)),
+                                            2.0,
+                                            Self::MATCH_COLOR,
+                                        );
+                                    }
+
                                     // Draw the text manually
                                     ui.painter().text(
                                         rect.center(),
                                         egui::Align2::CENTER_CENTER,
-                                        text,
+                                        text.clone(),
                                         egui::TextStyle::Monospace.resolve(ui.style()),
-                                        ui.visuals().text_color(),
+                                        text_color,
                                     );
 
-                                    // Check if this byte is in selection range
-                                    let is_selected = selection
-                                        .as_ref()
-                                        .map(|sel| sel.contains(off))
-                                        .unwrap_or(false);
-
                                     // Selection highlighting
                                     if is_selected {
                                         let r = rect.expand2(egui::vec2(1.0, 1.0));
-                                        ui.painter().rect_filled(
-                                            r,
-                                            2.0,
-                                            egui::Color32::from_rgb(100, 150, 255),
-                                        );
+                                        ui.painter().rect_filled(r, 2.0, selection_fill);
                                         // Redraw text to ensure it's on top
                                         ui.painter().text(
                                             r.center(),
                                             egui::Align2::CENTER_CENTER,
-                                            format!("{:02X}", data[off]),
+                                            text,
                                             egui::TextStyle::Monospace.resolve(ui.style()),
                                             ui.visuals().strong_text_color(),
                                         );
+                                        if let Some(sel) = match_selection {
+                                            Self::paint_range_outline(ui, r, off, i, sel);
+                                        }
+                                    }
+                                    if highlights.inspector_anchor == Some(off) {
+                                        Self::paint_anchor(ui, rect);
+                                    }
+
+                                    // Bookmark marker, outlined on top so it survives the selection fill
+                                    if highlights.is_bookmarked(off) {
+                                        ui.painter().rect_stroke(
+                                            rect.expand2(egui::vec2(1.0, 1.0)),
+                                            2.0,
+                                            egui::Stroke::new(1.5, Self::BOOKMARK_COLOR),
+                                            egui::StrokeKind::Inside,
+                                        );
                                     }
+                                    resp.context_menu(|ui| copy_offset(ui, off, offset_base));
                                     self.handle_drag(selection, &resp, DragStatus::Bytes(off));
                                 } else {
                                     ui.monospace("  ");
@@ -216,34 +897,46 @@ impl HexViewer {
                             // Render ASCII characters with individual interaction
                             ui.horizontal(|ui| {
                                 ui.spacing_mut().item_spacing.x = 0.0; // No spacing between chars
+                                let char_width = ui.fonts(|f| {
+                                    f.glyph_width(
+                                        &egui::TextStyle::Monospace.resolve(ui.style()),
+                                        'W',
+                                    )
+                                });
 
                                 for i in 0..(end - start) {
                                     let off = start + i;
                                     let byte = data[off];
-                                    let ch = if byte.is_ascii_graphic() {
-                                        byte as char
-                                    } else {
-                                        '.'
-                                    };
+                                    let ch = self.text_encoding.display_char(byte);
 
                                     // Check if this character is selected
                                     let is_selected = selection
                                         .as_ref()
                                         .map(|sel| sel.contains(off))
                                         .unwrap_or(false);
+                                    let tinted = !is_selected || translucent;
 
                                     // Create a clickable area for each character without text selection
-                                    let char_width = ui.fonts(|f| {
-                                        f.glyph_width(
-                                            &egui::TextStyle::Monospace.resolve(ui.style()),
-                                            'W',
-                                        )
-                                    });
                                     let (rect, resp) = ui.allocate_exact_size(
                                         egui::vec2(char_width, 18.0),
                                         egui::Sense::click_and_drag(),
                                     );
 
+                                    if tinted && highlights.is_diff(off) {
+                                        ui.painter().rect_filled(
+                                            rect.expand2(egui::vec2(0.0, 1.0)),
+                                            2.0,
+                                            Self::DIFF_COLOR,
+                                        );
+                                    }
+                                    if tinted && highlights.is_match(off) {
+                                        ui.painter().rect_filled(
+                                            rect.expand2(egui::vec2(0.0, 1.0)),
+                                            2.0,
+                                            Self::MATCH_COLOR,
+                                        );
+                                    }
+
                                     // Draw the character manually
                                     ui.painter().text(
                                         rect.center(),
@@ -256,11 +949,7 @@ impl HexViewer {
                                     // Highlight selected characters
                                     if is_selected {
                                         let r = rect.expand2(egui::vec2(0.0, 1.0));
-                                        ui.painter().rect_filled(
-                                            r,
-                                            2.0,
-                                            egui::Color32::from_rgb(100, 150, 255),
-                                        );
+                                        ui.painter().rect_filled(r, 2.0, selection_fill);
                                         // Redraw character on top
                                         ui.painter().text(
                                             r.center(),
@@ -269,14 +958,23 @@ impl HexViewer {
                                             egui::TextStyle::Monospace.resolve(ui.style()),
                                             ui.visuals().strong_text_color(),
                                         );
+                                        if let Some(sel) = match_selection {
+                                            Self::paint_range_outline(ui, r, off, i, sel);
+                                        }
+                                    }
+                                    if highlights.inspector_anchor == Some(off) {
+                                        Self::paint_anchor(ui, rect);
                                     }
 
-                                    self.handle_drag(selection, &resp, DragStatus::ASCII(off));
+                                    resp.context_menu(|ui| copy_offset(ui, off, offset_base));
+                                    self.handle_drag(selection, &resp, DragStatus::Ascii(off));
                                 }
                             });
                         });
                     });
                 });
+            self.scroll_offset = output.state.offset.y;
+            self.top_line = (self.scroll_offset / row_pitch) as usize;
         });
         // print!("Rendered: ");
         // for (i, &line) in rendered_lines.iter().enumerate() {
@@ -286,5 +984,58 @@ impl HexViewer {
         //     }
         // }
         // println!("");
+        edit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_hex_dump() {
+        let data: Vec<u8> = (0x41..0x41 + 20).collect();
+        let mut out = Vec::new();
+        HexViewer::write_hex_dump(&mut out, &data, 0x20, TextEncoding::Ascii).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "00000020: 4142 4344 4546 4748 494a 4b4c 4d4e 4f50  ABCDEFGHIJKLMNOP\n\
+             00000030: 5152 5354                                QRST\n"
+        );
+    }
+
+    #[test]
+    fn test_selection_fill() {
+        let visuals = egui::Visuals::dark();
+        let default = SelectionStyle::default();
+        assert_eq!(default.fill(&visuals), visuals.selection.bg_fill);
+        assert!(!default.is_translucent());
+
+        let style = SelectionStyle {
+            color: Some([255, 0, 0]),
+            opacity: 0.5,
+        };
+        assert_eq!(
+            style.fill(&visuals),
+            egui::Color32::from_rgba_unmultiplied(255, 0, 0, 128)
+        );
+        assert!(style.is_translucent());
+        // A stored opacity of zero still leaves the selection visible
+        let style = SelectionStyle {
+            opacity: 0.0,
+            ..style
+        };
+        assert_eq!(style.fill(&visuals).a(), 51);
+    }
+
+    #[test]
+    fn test_line_at_percent() {
+        assert_eq!(HexViewer::line_at_percent(0.0, 0), 0);
+        assert_eq!(HexViewer::line_at_percent(50.0, 0x1000), 0x80);
+        assert_eq!(HexViewer::line_at_percent(100.0, 0x1000), 0xFF);
+        assert_eq!(HexViewer::line_at_percent(100.0, 0x1001), 0x100);
+        assert_eq!(HexViewer::line_at_percent(-5.0, 0x1000), 0);
+        // Large files stay exact to the line
+        assert_eq!(HexViewer::line_at_percent(25.0, 8 << 30), 2 << 26);
     }
 }
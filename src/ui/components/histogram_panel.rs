@@ -0,0 +1,224 @@
+use crate::search::{AsyncStream, SearchState};
+use crate::ui::components::FileData;
+use crate::ui::util::Selection;
+use eframe::egui;
+
+struct Histogram {
+    counts: [u64; 256],
+    total: u64,
+    /// Shannon entropy in bits per byte, from 0 (one value) to 8 (uniform).
+    entropy: f64,
+}
+
+impl Histogram {
+    fn new(counts: [u64; 256]) -> Self {
+        let total = counts.iter().sum::<u64>();
+        let entropy = counts
+            .iter()
+            .filter(|&&n| n > 0)
+            .map(|&n| {
+                let p = n as f64 / total as f64;
+                -p * p.log2()
+            })
+            .sum();
+        Self {
+            counts,
+            total,
+            entropy,
+        }
+    }
+}
+
+// Range and data revision a histogram belongs to
+type Key = (Option<Selection>, u64);
+
+// Counts for a range the worker is still going through
+struct Counting {
+    key: Key,
+    stream: AsyncStream<[u64; 256]>,
+    counts: [u64; 256],
+}
+
+/// Counts each byte value in `range`, or the whole data, on a worker thread
+/// that sends the counts of one chunk at a time.
+fn count_bytes(data: FileData, range: Option<Selection>) -> AsyncStream<[u64; 256]> {
+    // Small enough to cancel promptly while a selection is dragged
+    const CHUNK: usize = 1 << 20;
+    AsyncStream::spawn(4, move |tx| {
+        let data = data.as_slice();
+        let bytes = match range {
+            Some(sel) => {
+                let upper = sel.upper().min(data.len() - 1);
+                &data[sel.lower().min(upper)..=upper]
+            }
+            None => data,
+        };
+        for chunk in bytes.chunks(CHUNK) {
+            let mut counts = [0u64; 256];
+            for &b in chunk {
+                counts[b as usize] += 1;
+            }
+            if tx.send(counts).is_err() {
+                return;
+            }
+        }
+    })
+}
+
+pub struct HistogramPanel {
+    selection_only: bool,
+    // Histogram of the last range counted, `None` if its worker failed
+    cached: Option<(Key, Option<Histogram>)>,
+    counting: Option<Counting>,
+}
+
+impl Default for HistogramPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HistogramPanel {
+    const CHART_HEIGHT: f32 = 80.0;
+
+    pub fn new() -> Self {
+        Self {
+            selection_only: false,
+            cached: None,
+            counting: None,
+        }
+    }
+
+    fn stop(&mut self) {
+        if let Some(counting) = self.counting.take()
+            && let Err(e) = counting.stream.cancel()
+        {
+            log::warn!("Histogram thread failed: {}", e);
+        }
+    }
+
+    /// Starts counting `key`'s range unless it is cached or already being
+    /// counted, cancelling the count of any other range, and collects what
+    /// the worker has counted so far.
+    fn update(&mut self, key: Key, data: &FileData) {
+        if self.cached.as_ref().is_some_and(|(k, _)| *k == key) {
+            self.stop();
+            return;
+        }
+        if self.counting.as_ref().is_none_or(|c| c.key != key) {
+            self.stop();
+            self.counting = Some(Counting {
+                key,
+                stream: count_bytes(data.clone(), key.0),
+                counts: [0; 256],
+            });
+        }
+        let Counting { stream, counts, .. } = self.counting.as_mut().unwrap();
+        let state = stream.drain(|chunk| {
+            for (count, n) in counts.iter_mut().zip(chunk) {
+                *count += n;
+            }
+        });
+        match state {
+            SearchState::Pending => {}
+            SearchState::Finished => {
+                let histogram = Histogram::new(*counts);
+                self.stop();
+                self.cached = Some((key, Some(histogram)));
+            }
+            SearchState::Panicked => {
+                self.stop();
+                self.cached = Some((key, None));
+            }
+        }
+    }
+
+    fn paint_chart(ui: &mut egui::Ui, histogram: &Histogram) {
+        let width = ui.available_width();
+        let (rect, response) =
+            ui.allocate_exact_size(egui::vec2(width, Self::CHART_HEIGHT), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+
+        let max = histogram.counts.iter().copied().max().unwrap_or(0).max(1);
+        let bar_width = rect.width() / 256.0;
+        let color = ui.visuals().selection.bg_fill;
+        for (value, &count) in histogram.counts.iter().enumerate() {
+            if count == 0 {
+                continue;
+            }
+            let height = (count as f32 / max as f32 * rect.height()).max(1.0);
+            let left = rect.left() + value as f32 * bar_width;
+            let bar = egui::Rect::from_min_max(
+                egui::pos2(left, rect.bottom() - height),
+                egui::pos2(left + bar_width.max(1.0), rect.bottom()),
+            );
+            painter.rect_filled(bar, 0.0, color);
+        }
+
+        if let Some(pos) = response.hover_pos() {
+            let value = (((pos.x - rect.left()) / bar_width) as usize).min(255);
+            let count = histogram.counts[value];
+            let percent = count as f64 / histogram.total as f64 * 100.0;
+            response.on_hover_text_at_pointer(format!(
+                "0x{:02X} ({}): {} ({:.2}%)",
+                value, value, count, percent
+            ));
+        }
+    }
+
+    pub fn render(
+        &mut self,
+        ui: &mut egui::Ui,
+        selection: Option<Selection>,
+        file_data: Option<FileData>,
+        revision: u64,
+    ) {
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Byte Histogram");
+                    ui.checkbox(&mut self.selection_only, "Selection only");
+                });
+
+                let Some(data) = file_data.filter(|d| !d.as_slice().is_empty()) else {
+                    self.stop();
+                    ui.label("No file loaded");
+                    return;
+                };
+                let range = match (self.selection_only, selection) {
+                    (false, _) => None,
+                    (true, Some(sel)) => Some(sel),
+                    (true, None) => {
+                        self.stop();
+                        ui.label("No selection");
+                        return;
+                    }
+                };
+                let key = (range, revision);
+                self.update(key, &data);
+                let histogram = match &self.cached {
+                    Some((k, Some(histogram))) if *k == key => histogram,
+                    Some((k, None)) if *k == key => {
+                        ui.label("Failed to count bytes");
+                        return;
+                    }
+                    // The spinner asks for the frames that collect the counts
+                    _ => {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label("Counting bytes...");
+                        });
+                        return;
+                    }
+                };
+                let distinct = histogram.counts.iter().filter(|&&n| n > 0).count();
+                ui.label(format!(
+                    "{} bytes, {} distinct, entropy {:.3} bits/byte",
+                    histogram.total, distinct, histogram.entropy
+                ));
+                Self::paint_chart(ui, histogram);
+            });
+        });
+    }
+}
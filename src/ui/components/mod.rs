@@ -3,9 +3,31 @@ pub mod file_panel;
 pub mod search_control_panel;
 pub mod search_results_panel;
 pub mod data_inspector;
+pub mod bookmarks_panel;
+pub mod checksum_panel;
+pub mod toasts;
+pub mod scan_panel;
+pub mod struct_panel;
+pub mod compare_panel;
+pub mod histogram_panel;
+pub mod bit_view_panel;
+pub mod batch_search_panel;
+pub mod strings_panel;
+pub mod runs_panel;
 
-pub use hex_viewer::HexViewer;
+pub use hex_viewer::{HexViewer, Highlights, SelectionStyle};
 pub use data_inspector::DataInspector;
-pub use file_panel::FilePanel;
-pub use search_control_panel::SearchControlPanel;
-pub use search_results_panel::SearchResultsPanel;
+pub use file_panel::{FileData, FilePanel, FileParts};
+pub use search_control_panel::{SearchControlPanel, SearchRequest};
+pub use search_results_panel::{ResultsAction, SearchResultsPanel, SearchStatus};
+pub use bookmarks_panel::BookmarksPanel;
+pub use checksum_panel::ChecksumPanel;
+pub use toasts::{ToastLevel, Toasts};
+pub use scan_panel::{ScanFilter, ScanPanel};
+pub use struct_panel::StructPanel;
+pub use compare_panel::ComparePanel;
+pub use histogram_panel::HistogramPanel;
+pub use bit_view_panel::BitViewPanel;
+pub use batch_search_panel::{BatchAction, BatchSearchPanel};
+pub use strings_panel::StringsPanel;
+pub use runs_panel::RunsPanel;
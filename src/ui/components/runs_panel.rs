@@ -0,0 +1,54 @@
+use eframe::egui;
+
+/// Finds the runs of one repeated byte in the file, such as zero padding,
+/// alignment gaps or slack space. The runs are listed as search results,
+/// so they are stepped through and selected like matches.
+pub struct RunsPanel {
+    byte: u8,
+    min_len: usize,
+}
+
+impl Default for RunsPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RunsPanel {
+    pub fn new() -> Self {
+        Self {
+            byte: 0x00,
+            min_len: 16,
+        }
+    }
+
+    /// Returns the byte and the shortest run to list when the user asks
+    /// for the runs to be found.
+    pub fn render(&mut self, ui: &mut egui::Ui, has_file: bool) -> Option<(u8, usize)> {
+        let mut find = None;
+
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.label("Byte Runs");
+                ui.add(
+                    egui::DragValue::new(&mut self.byte)
+                        .hexadecimal(2, false, true)
+                        .prefix("0x"),
+                )
+                .on_hover_text("Byte the runs repeat");
+                ui.label("at least");
+                ui.add(egui::DragValue::new(&mut self.min_len).range(1..=usize::MAX))
+                    .on_hover_text("Shortest run to list, in bytes");
+                if ui
+                    .add_enabled(has_file, egui::Button::new("Find"))
+                    .on_hover_text("List every run of the byte in the file as search results")
+                    .clicked()
+                {
+                    find = Some((self.byte, self.min_len));
+                }
+            });
+        });
+
+        find
+    }
+}
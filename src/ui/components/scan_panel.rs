@@ -0,0 +1,97 @@
+use crate::search::Endianness;
+use eframe::egui;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// How a result's value in the previous file must relate to its value in
+/// the next one for the result to be kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+pub enum ScanFilter {
+    Changed,
+    Unchanged,
+    Increased,
+    Decreased,
+    EqualsValue,
+}
+
+impl std::fmt::Display for ScanFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ScanFilter::Changed => "Changed",
+            ScanFilter::Unchanged => "Unchanged",
+            ScanFilter::Increased => "Increased",
+            ScanFilter::Decreased => "Decreased",
+            ScanFilter::EqualsValue => "Equals value",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl ScanFilter {
+    /// `needle` is the typed search value, used by `EqualsValue`.
+    pub fn keeps(
+        &self,
+        old: &[u8],
+        new: &[u8],
+        needle: &[u8],
+        signed: bool,
+        endianness: Endianness,
+    ) -> bool {
+        let value = |b| endianness.read_int(b, signed);
+        match self {
+            ScanFilter::Changed => old != new,
+            ScanFilter::Unchanged => old == new,
+            ScanFilter::Increased => value(new) > value(old),
+            ScanFilter::Decreased => value(new) < value(old),
+            ScanFilter::EqualsValue => new == needle,
+        }
+    }
+}
+
+/// Controls for narrowing the current results against a later snapshot
+/// of the same data, like a memory scanner's "next scan".
+pub struct ScanPanel {
+    filter: ScanFilter,
+}
+
+impl Default for ScanPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScanPanel {
+    pub fn new() -> Self {
+        Self {
+            filter: ScanFilter::Changed,
+        }
+    }
+
+    /// Returns the filter to apply when the user asks for a rescan.
+    pub fn render(&mut self, ui: &mut egui::Ui, enabled: bool) -> Option<ScanFilter> {
+        let mut requested = None;
+        ui.horizontal(|ui| {
+            ui.add_enabled_ui(enabled, |ui| {
+                ui.label("Next file:");
+                egui::ComboBox::from_id_salt("ScanPanel.Filter")
+                    .width(100.)
+                    .selected_text(format!("{}", self.filter))
+                    .show_ui(ui, |ui| {
+                        for filter in ScanFilter::iter() {
+                            ui.selectable_value(&mut self.filter, filter, format!("{}", filter));
+                        }
+                    });
+                if ui
+                    .button("Rescan...")
+                    .on_hover_text(
+                        "Open another snapshot and keep the results whose value passes the filter",
+                    )
+                    .clicked()
+                {
+                    requested = Some(self.filter);
+                }
+            });
+        });
+        requested
+    }
+}
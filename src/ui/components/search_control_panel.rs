@@ -1,18 +1,56 @@
 use core::f32;
 
 use eframe::egui;
+use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
 use crate::search::Endianness;
 use crate::ui::util::{SearchType, Encoding};
+
+/// What the user asked the panel to search for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchRequest {
+    /// Every match in the file.
+    All,
+    /// The first match at or after the selection.
+    NextFromCursor,
+    /// The first offset from the selection where the value stops repeating.
+    FirstDiffering,
+    /// Every match of the selected bytes.
+    Selection,
+}
+
+// Persisted between runs, so every field falls back to `new()` when missing
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
 pub struct SearchControlPanel {
     search_type: SearchType,
     search_input: String,
     endianness: Endianness,
     encoding: Encoding,
     is_signed: bool,
+    count_only: bool,
+    prepend_bom: bool,
+    live: bool,
+    wrap_around: bool,
+    search_on_reload: bool,
+    // Results listed before a search stops itself, at most `RESULTS_CAP`
+    max_results: usize,
+    #[serde(skip)]
+    focus_requested: bool,
+    #[serde(skip)]
+    input_changed: bool,
+}
+
+impl Default for SearchControlPanel {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SearchControlPanel {
+    /// Hard cap on the results one search may hold, whatever the setting says.
+    pub const RESULTS_CAP: usize = 10_000_000;
+
     pub fn new() -> Self {
         Self {
             search_type: SearchType::Bit8,
@@ -20,9 +58,22 @@ impl SearchControlPanel {
             endianness: Endianness::LittleEndian,
             encoding: Encoding::UTF8,
             is_signed: false,
+            count_only: false,
+            prepend_bom: false,
+            live: true,
+            wrap_around: true,
+            search_on_reload: false,
+            max_results: 1_000_000,
+            focus_requested: false,
+            input_changed: false,
         }
     }
 
+    /// Moves keyboard focus to the value field on the next frame.
+    pub fn request_focus(&mut self) {
+        self.focus_requested = true;
+    }
+
     pub fn get_search_type(&self) -> SearchType {
         self.search_type
     }
@@ -43,8 +94,58 @@ impl SearchControlPanel {
         self.encoding
     }
 
-    pub fn render(&mut self, ui: &mut egui::Ui) -> bool {
-        let mut search_requested = false;
+    pub fn get_count_only(&self) -> bool {
+        self.count_only
+    }
+
+    pub fn get_prepend_bom(&self) -> bool {
+        self.prepend_bom
+    }
+
+    pub fn get_wrap_around(&self) -> bool {
+        self.wrap_around
+    }
+
+    /// Whether a reloaded file is searched again for the tab's last search.
+    pub fn get_search_on_reload(&self) -> bool {
+        self.search_on_reload
+    }
+
+    pub fn set_endianness(&mut self, endianness: Endianness) {
+        self.endianness = endianness;
+    }
+
+    pub fn get_max_results(&self) -> usize {
+        self.max_results.clamp(1, Self::RESULTS_CAP)
+    }
+
+    /// Switches to a byte search for `bytes`, written as hex.
+    pub fn set_bytes_input(&mut self, bytes: &[u8]) {
+        self.search_type = SearchType::Bytes;
+        self.search_input = bytes
+            .iter()
+            .map(|b| format!("{:02X}", b))
+            .collect::<Vec<_>>()
+            .join(" ");
+    }
+
+    /// Whether the search changed since the last call and should be rerun
+    /// live. Always `false` with live search turned off.
+    pub fn take_input_changed(&mut self) -> bool {
+        std::mem::take(&mut self.input_changed) && self.live
+    }
+
+    pub fn render(&mut self, ui: &mut egui::Ui, has_selection: bool) -> Option<SearchRequest> {
+        let mut search_requested = None;
+        let before = (
+            self.search_type,
+            self.search_input.clone(),
+            self.endianness,
+            self.encoding,
+            self.is_signed,
+            self.count_only,
+            self.prepend_bom,
+        );
 
         // Search controls section
         ui.group(|ui| {
@@ -53,7 +154,7 @@ impl SearchControlPanel {
             let _resp = ui.horizontal(|ui| {
                 // Search type dropdown
                 egui::ComboBox::from_id_salt("SearchControlPanel.Type")
-                    .width(60.)
+                    .width(80.)
                     .selected_text(format!("{}", self.search_type))
                     .show_ui(ui, |ui| {
                         for search_type in SearchType::iter() {
@@ -65,12 +166,30 @@ impl SearchControlPanel {
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     // Search button
                     if ui.button("Search").clicked() {
-                        search_requested = true;
+                        search_requested = Some(SearchRequest::All);
                     }
-                    let _resp = ui.add(
+                    if ui.button("Skip").on_hover_text("Find the end of a run of the value from the selection").clicked() {
+                        search_requested = Some(SearchRequest::FirstDiffering);
+                    }
+                    if ui.button("Next").on_hover_text("Find the next match from the selection").clicked() {
+                        search_requested = Some(SearchRequest::NextFromCursor);
+                    }
+                    if ui.add_enabled(has_selection, egui::Button::new("Find selection"))
+                        .on_hover_text("Search for the selected bytes")
+                        .clicked()
+                    {
+                        search_requested = Some(SearchRequest::Selection);
+                    }
+                    let resp = ui.add(
                         egui::TextEdit::singleline(&mut self.search_input)
                             .desired_width(f32::INFINITY)
                     );
+                    if resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        search_requested = Some(SearchRequest::All);
+                    }
+                    if std::mem::take(&mut self.focus_requested) {
+                        resp.request_focus();
+                    }
                     // println!("Input: {}", _resp.rect.width());
                 });
             });
@@ -107,17 +226,56 @@ impl SearchControlPanel {
                     ui.label("Encoding");
                     egui::ComboBox::from_id_salt("SearchControlPanel.Encoding")
                     .selected_text(format!("{}", self.encoding))
-                    .width(70.)
+                    .width(100.)
                     .show_ui(ui, |ui| {
-                        for encoding in Encoding::iter() {
+                        for encoding in Encoding::iter().filter(Encoding::is_available) {
                             ui.selectable_value(&mut self.encoding, encoding, format!("{}", encoding));
                         }
                     });
                 });
+                ui.add_enabled_ui(self.search_type.is_encoding_enabled() && self.encoding.bom().is_some(), |ui| {
+                    ui.checkbox(&mut self.prepend_bom, "BOM")
+                        .on_hover_text("Prepend the encoding's byte order mark to the string");
+                });
+
+                ui.separator();
+
+                ui.checkbox(&mut self.count_only, "Count only")
+                    .on_hover_text("Only count the matches instead of listing them");
+
+                ui.separator();
+
+                ui.checkbox(&mut self.live, "Live")
+                    .on_hover_text("Search as you type");
+                ui.checkbox(&mut self.wrap_around, "Wrap")
+                    .on_hover_text("Let Next continue from the start of the file");
+                ui.checkbox(&mut self.search_on_reload, "On reload")
+                    .on_hover_text("Search a reloaded file again for the last value searched in its tab");
 
+                ui.separator();
+
+                ui.label("Max results");
+                ui.add(
+                    egui::DragValue::new(&mut self.max_results)
+                        .range(1..=Self::RESULTS_CAP)
+                        .speed(1000),
+                )
+                .on_hover_text("Stop the search once this many matches are listed");
             });
         });
 
+        let after = (
+            self.search_type,
+            self.search_input.clone(),
+            self.endianness,
+            self.encoding,
+            self.is_signed,
+            self.count_only,
+            self.prepend_bom,
+        );
+        if before != after && search_requested.is_none() {
+            self.input_changed = true;
+        }
         search_requested
     }
 }
@@ -1,15 +1,81 @@
-use crate::ui::util::SearchResult;
+use crate::search::Endianness;
+use crate::ui::components::FileParts;
+use crate::ui::util::{OffsetFormat, SearchResult, copy_offset};
 use eframe::egui;
 use egui_extras::{Column, TableBuilder};
+use std::io::{self, Write};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchStatus {
+    Idle,
+    Searching,
+    Finished,
+    /// Stopped once the results reached the configured maximum.
+    LimitReached,
+    /// Count-only search still running, with the matches counted so far.
+    Counting(usize),
+    /// Count-only search: the number of matches, with no offsets listed.
+    Counted(usize),
+}
+
+/// What the user asked the panel to do with its results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultsAction {
+    /// Jump to the result at this index.
+    Goto(usize),
+    /// Write the results out to a file.
+    Export,
+    /// Drop the results and stop the search.
+    Clear,
+    /// Search again with the values in this byte order.
+    SwitchEndianness(Endianness),
+}
 
 pub struct SearchResultsPanel {
     search_results: Vec<SearchResult>,
+    // The app stops pulling from the search once this many results are held
+    result_limit: usize,
+    // Row highlighted for keyboard navigation, moved with Up/Down while the
+    // table has focus
+    focused_row: Option<usize>,
+    // Scroll the highlighted row into view on the next render
+    scroll_to_focused: bool,
+    // Hex of the bytes the results were searched for
+    searched_for: Option<String>,
+    // Values of a search for several, named in the results by `pattern`
+    values: Vec<String>,
+    // Matches in the other byte order, for a search that found none
+    endianness_hint: Option<(usize, Endianness)>,
+}
+
+impl Default for SearchResultsPanel {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SearchResultsPanel {
+    pub const PAGE_SIZE: usize = 100_000;
+    /// Bytes shown per row: the match itself, then what follows it.
+    const CONTEXT_BYTES: usize = 8;
+
+    fn hex_bytes(bytes: &[u8]) -> String {
+        bytes
+            .iter()
+            .map(|b| format!("{:02X}", b))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
     pub fn new() -> Self {
         Self {
             search_results: Vec::new(),
+            result_limit: Self::PAGE_SIZE,
+            focused_row: None,
+            scroll_to_focused: false,
+            searched_for: None,
+            values: Vec::new(),
+            endianness_hint: None,
         }
     }
 
@@ -19,16 +85,99 @@ impl SearchResultsPanel {
 
     pub fn set_search_results(&mut self, results: Vec<SearchResult>) {
         self.search_results = results;
+        self.focused_row = None;
+    }
+
+    /// Highlights `row` and scrolls it into view, e.g. after F3 moved to it.
+    pub fn set_focused_row(&mut self, row: usize) {
+        if self.focused_row != Some(row) {
+            self.focused_row = Some(row);
+            self.scroll_to_focused = true;
+        }
+    }
+
+    pub fn get_result_limit(&self) -> usize {
+        self.result_limit
     }
 
     pub fn clear_results(&mut self) {
         self.search_results.clear();
+        self.result_limit = Self::PAGE_SIZE;
+        self.focused_row = None;
+        self.searched_for = None;
+        self.values.clear();
+        self.endianness_hint = None;
+    }
+
+    /// Suggests searching again in `endianness`, which has `count` matches.
+    pub fn set_endianness_hint(&mut self, count: usize, endianness: Endianness) {
+        self.endianness_hint = Some((count, endianness));
+    }
+
+    /// Shows which bytes the results are for, kept until they are cleared.
+    /// With `values` each result also names the one it matched.
+    pub fn set_searched_for(&mut self, preview: String, values: Vec<String>) {
+        self.searched_for = Some(preview);
+        self.values = values;
+    }
+
+    /// Writes the results as CSV, one `index,offset,length` line each, with
+    /// offsets written the way the panel shows them.
+    pub fn write_csv(&self, out: &mut impl Write, offset_base: OffsetFormat) -> io::Result<()> {
+        writeln!(out, "index,offset,length")?;
+        for result in &self.search_results {
+            writeln!(
+                out,
+                "{},{},{}",
+                result.index,
+                offset_base.format(result.offset),
+                result.len
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Moves the highlighted row with Up/Down while the table has focus.
+    /// Returns the row to jump to when Enter is pressed, and whether the
+    /// highlight moved.
+    fn handle_keys(&mut self, ui: &egui::Ui, focus_id: egui::Id) -> (Option<usize>, bool) {
+        if !ui.memory(|m| m.has_focus(focus_id)) || self.search_results.is_empty() {
+            return (None, false);
+        }
+        // Keep the arrow keys from moving focus to another widget
+        ui.memory_mut(|m| {
+            m.set_focus_lock_filter(
+                focus_id,
+                egui::EventFilter {
+                    vertical_arrows: true,
+                    ..Default::default()
+                },
+            )
+        });
+        let last = self.search_results.len() - 1;
+        let current = self.focused_row.map(|row| row.min(last));
+        let (up, down, enter) = ui.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+            )
+        });
+        let moved = match (up, down, current) {
+            (true, _, Some(row)) => Some(row.saturating_sub(1)),
+            (_, true, Some(row)) => Some((row + 1).min(last)),
+            (true, _, None) | (_, true, None) => Some(0),
+            _ => None,
+        };
+        if moved.is_some() {
+            self.focused_row = moved;
+        }
+        (enter.then_some(current).flatten(), moved.is_some())
     }
 
     pub fn add_search_results(&mut self, mut results: Vec<SearchResult>) {
         // Update indices to be continuous
         let start_index = self.search_results.len();
-        println!("Adding {} to search results", results.len());
         for (i, result) in results.iter_mut().enumerate() {
             result.index = start_index + i;
         }
@@ -36,20 +185,113 @@ impl SearchResultsPanel {
         self.search_results.extend(results);
     }
 
-    pub fn render(&mut self, ui: &mut egui::Ui) -> Option<usize> {
-        let mut selected_offset = None;
+    pub fn render(
+        &mut self,
+        ui: &mut egui::Ui,
+        offset_base: OffsetFormat,
+        status: SearchStatus,
+        file_data: Option<&[u8]>,
+        parts: Option<&FileParts>,
+    ) -> Option<ResultsAction> {
+        let data = file_data.unwrap_or_default();
+        let focus_id = ui.id().with("SearchResultsPanel.Table");
+        let (mut selected_index, moved) = self.handle_keys(ui, focus_id);
+        let mut action = None;
+        let scroll = moved || std::mem::take(&mut self.scroll_to_focused);
+        let has_focus = ui.memory(|m| m.has_focus(focus_id));
+        let mut take_focus = false;
 
         // Search results section using TableBuilder
-        ui.group(|ui| {
+        let group = ui.group(|ui| {
             ui.vertical(|ui| {
-                ui.label("Search Results");
+                ui.horizontal(|ui| {
+                    ui.label("Search Results");
+                    let count = self.search_results.len();
+                    let noun = if count == 1 { "match" } else { "matches" };
+                    match status {
+                        SearchStatus::Idle => {}
+                        SearchStatus::Searching if count >= self.result_limit => {
+                            ui.label(format!("showing first {} {}", count, noun));
+                            if ui.button("Load more").clicked() {
+                                self.result_limit += Self::PAGE_SIZE;
+                            }
+                        }
+                        // Nothing found yet is not the same as nothing found
+                        SearchStatus::Searching if count == 0 => {
+                            ui.spinner();
+                            ui.label("Searching...");
+                        }
+                        SearchStatus::Searching => {
+                            ui.spinner();
+                            ui.label(format!("Searching... {} {} so far", count, noun));
+                        }
+                        SearchStatus::Counting(n) => {
+                            ui.spinner();
+                            ui.label(format!("Counting... {} so far", n));
+                        }
+                        SearchStatus::Finished | SearchStatus::Counted(0) if count == 0 => {
+                            ui.label("No matches found");
+                            if let Some((n, endianness)) = self.endianness_hint {
+                                let name = match endianness {
+                                    Endianness::BigEndian => "big-endian",
+                                    Endianness::LittleEndian => "little-endian",
+                                };
+                                ui.label(format!("({} as {})", n, name));
+                                if ui.button("Switch").clicked() {
+                                    action = Some(ResultsAction::SwitchEndianness(endianness));
+                                }
+                            }
+                        }
+                        SearchStatus::Finished => {
+                            ui.label(format!("{} {}", count, noun));
+                        }
+                        SearchStatus::LimitReached => {
+                            ui.label(format!("stopped at {} {} (limit reached)", count, noun))
+                                .on_hover_text("Raise Max results and search again to find more");
+                        }
+                        SearchStatus::Counted(n) => {
+                            let noun = if n == 1 { "match" } else { "matches" };
+                            ui.label(format!("{} {} (count only)", n, noun));
+                        }
+                    }
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let has_results = !self.search_results.is_empty();
+                        if ui
+                            .add_enabled(status != SearchStatus::Idle, egui::Button::new("Clear"))
+                            .clicked()
+                        {
+                            action = Some(ResultsAction::Clear);
+                        }
+                        if ui
+                            .add_enabled(has_results, egui::Button::new("Export..."))
+                            .on_hover_text("Save the listed results as CSV")
+                            .clicked()
+                        {
+                            action = Some(ResultsAction::Export);
+                        }
+                    });
+                });
+                if let Some(preview) = &self.searched_for {
+                    ui.label(
+                        egui::RichText::new(format!("[{}]", preview))
+                            .monospace()
+                            .weak(),
+                    )
+                    .on_hover_text("The bytes searched for");
+                }
 
                 // Use TableBuilder which handles scrolling automatically
-                TableBuilder::new(ui)
+                let mut table = TableBuilder::new(ui);
+                if scroll && let Some(row) = self.focused_row {
+                    table = table.scroll_to_row(row, Some(egui::Align::Center));
+                }
+                table
                     .striped(true)
+                    .sense(egui::Sense::click())
                     .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
                     .column(Column::exact(140.)) // Index column
-                    .column(Column::remainder()) // Offset column
+                    .column(Column::auto().at_least(90.)) // Offset column
+                    .column(Column::remainder()) // Matched bytes column
                     .column(Column::exact(50.)) // Action column
                     .header(20.0, |mut header| {
                         header.col(|ui| {
@@ -58,6 +300,9 @@ impl SearchResultsPanel {
                         header.col(|ui| {
                             ui.strong("Offset");
                         });
+                        header.col(|ui| {
+                            ui.strong("Bytes");
+                        });
                         header.col(|ui| {
                             ui.strong("Action");
                         });
@@ -66,28 +311,130 @@ impl SearchResultsPanel {
                         body.rows(18.0, self.search_results.len(), |mut row| {
                             let row_index = row.index();
                             let result = &self.search_results[row_index];
+                            row.set_selected(self.focused_row == Some(row_index));
 
                             row.col(|ui| {
                                 ui.label(
                                     egui::RichText::new(format!("{}", result.index))
                                         .text_style(egui::TextStyle::Monospace),
                                 );
+                                if let Some(value) = self.values.get(result.pattern) {
+                                    ui.label(egui::RichText::new(format!("= {}", value)).weak());
+                                }
                             });
                             row.col(|ui| {
                                 ui.label(
-                                    egui::RichText::new(format!("0x{:08X}", result.offset))
+                                    egui::RichText::new(offset_base.format(result.offset))
                                         .text_style(egui::TextStyle::Monospace),
+                                )
+                                .context_menu(|ui| copy_offset(ui, result.offset, offset_base));
+                                // Joined files also name the file the match starts in
+                                if let Some((path, local)) =
+                                    parts.and_then(|p| p.locate(result.offset))
+                                {
+                                    let name = path.file_name().unwrap_or(path.as_os_str());
+                                    ui.label(
+                                        egui::RichText::new(format!(
+                                            "{} {}",
+                                            name.to_string_lossy(),
+                                            offset_base.base.format(local)
+                                        ))
+                                        .weak(),
+                                    )
+                                    .on_hover_text(path.display().to_string());
+                                }
+                            });
+                            row.col(|ui| {
+                                let start = result.offset.min(data.len());
+                                let end = (start + Self::CONTEXT_BYTES).min(data.len());
+                                let match_end = (start + result.len).min(end);
+                                let truncated = start + result.len > end;
+                                ui.spacing_mut().item_spacing.x = 0.0;
+                                ui.label(
+                                    egui::RichText::new(Self::hex_bytes(&data[start..match_end]))
+                                        .text_style(egui::TextStyle::Monospace)
+                                        .strong(),
                                 );
+                                if truncated {
+                                    ui.label(
+                                        egui::RichText::new(" …")
+                                            .text_style(egui::TextStyle::Monospace),
+                                    );
+                                } else if match_end < end {
+                                    ui.label(
+                                        egui::RichText::new(format!(
+                                            " {}",
+                                            Self::hex_bytes(&data[match_end..end])
+                                        ))
+                                        .text_style(egui::TextStyle::Monospace)
+                                        .weak(),
+                                    );
+                                }
                             });
                             row.col(|ui| {
                                 if ui.button("Go").clicked() {
-                                    selected_offset = Some(result.offset);
+                                    selected_index = Some(row_index);
                                 }
                             });
+                            if row.response().clicked() {
+                                self.focused_row = Some(row_index);
+                                take_focus = true;
+                            }
                         });
                     });
             })
         });
-        return selected_offset;
+        // Takes keyboard focus without taking clicks from the rows and buttons
+        let rect = group.response.rect;
+        let focus = ui.interact(rect, focus_id, egui::Sense::focusable_noninteractive());
+        if has_focus {
+            ui.painter().rect_stroke(
+                rect,
+                ui.visuals().widgets.noninteractive.corner_radius,
+                ui.visuals().selection.stroke,
+                egui::StrokeKind::Inside,
+            );
+        }
+        if let Some(row) = selected_index {
+            self.focused_row = Some(row);
+            take_focus = true;
+        }
+        if take_focus {
+            focus.request_focus();
+        }
+        selected_index.map(ResultsAction::Goto).or(action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::util::OffsetBase;
+
+    #[test]
+    fn test_write_csv() {
+        let mut panel = SearchResultsPanel::new();
+        panel.add_search_results(vec![
+            SearchResult {
+                index: 0,
+                offset: 0x10,
+                len: 2,
+                pattern: 0,
+            },
+            SearchResult {
+                index: 0,
+                offset: 0x2A,
+                len: 2,
+                pattern: 0,
+            },
+        ]);
+        let mut out = Vec::new();
+        panel
+            .write_csv(&mut out, OffsetBase::Decimal.into())
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "index,offset,length\n0,16,2\n1,42,2\n"
+        );
     }
 }
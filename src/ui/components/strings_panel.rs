@@ -0,0 +1,206 @@
+use std::time::{Duration, Instant};
+
+use crate::search::SearchState;
+use crate::strings::{self, AsyncStrings, FoundString, StringEncoding};
+use crate::ui::components::FileData;
+use crate::ui::util::{OffsetFormat, copy_offset};
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+use strum::IntoEnumIterator;
+
+/// Lists the runs of printable text in the file, like the `strings` utility,
+/// found on a worker thread so large files do not stall the UI.
+pub struct StringsPanel {
+    min_chars: usize,
+    encoding: StringEncoding,
+    scan: Option<AsyncStrings>,
+    strings: Vec<FoundString>,
+    // Whether the list stopped at `MAX_STRINGS`
+    limited: bool,
+    // Last failure, until the app picks it up with `take_error`
+    error: Option<String>,
+}
+
+impl Default for StringsPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StringsPanel {
+    // Time spent collecting strings per frame, so the UI stays responsive
+    const FRAME_BUDGET: Duration = Duration::from_millis(4);
+    // Most strings listed, so a file of text cannot exhaust memory
+    const MAX_STRINGS: usize = 1_000_000;
+
+    pub fn new() -> Self {
+        Self {
+            min_chars: 4,
+            encoding: StringEncoding::Ascii,
+            scan: None,
+            strings: Vec::new(),
+            limited: false,
+            error: None,
+        }
+    }
+
+    pub fn take_error(&mut self) -> Option<String> {
+        self.error.take()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.scan.is_some()
+    }
+
+    fn start(&mut self, data: FileData) {
+        self.clear();
+        self.scan = Some(strings::scan_strings(data, self.min_chars, self.encoding));
+    }
+
+    fn stop(&mut self) {
+        if let Some(scan) = self.scan.take()
+            && let Err(e) = scan.cancel()
+        {
+            log::warn!("String scan thread failed: {}", e);
+        }
+    }
+
+    /// Cancels the scan and forgets the strings, e.g. when another file is opened.
+    pub fn clear(&mut self) {
+        self.stop();
+        self.strings.clear();
+        self.limited = false;
+    }
+
+    /// Collects strings for part of the frame.
+    pub fn update(&mut self) {
+        let Some(scan) = &self.scan else {
+            return;
+        };
+        let started = Instant::now();
+        let state = loop {
+            if self.strings.len() >= Self::MAX_STRINGS {
+                self.limited = true;
+                break SearchState::Finished;
+            }
+            match scan.try_get() {
+                Ok(found) => self.strings.push(found),
+                Err(state) => break state,
+            }
+            // Checking the clock per string would cost more than collecting it
+            if self.strings.len().is_multiple_of(1024) && started.elapsed() >= Self::FRAME_BUDGET {
+                return;
+            }
+        };
+        match state {
+            SearchState::Pending => {}
+            SearchState::Finished => self.stop(),
+            SearchState::Panicked => {
+                self.stop();
+                self.error = Some("String scan failed unexpectedly".into());
+            }
+        }
+    }
+
+    /// Returns the offset and length of a string the user clicked.
+    pub fn render(
+        &mut self,
+        ui: &mut egui::Ui,
+        file_data: Option<FileData>,
+        offset_format: OffsetFormat,
+    ) -> Option<(usize, usize)> {
+        let mut clicked = None;
+
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Strings");
+                    ui.add(egui::DragValue::new(&mut self.min_chars).range(1..=256))
+                        .on_hover_text("Fewest characters in a run to list it");
+                    egui::ComboBox::from_id_salt("StringsPanel.Encoding")
+                        .selected_text(self.encoding.to_string())
+                        .width(60.)
+                        .show_ui(ui, |ui| {
+                            for encoding in StringEncoding::iter() {
+                                ui.selectable_value(
+                                    &mut self.encoding,
+                                    encoding,
+                                    encoding.to_string(),
+                                );
+                            }
+                        });
+                    if self.is_running() {
+                        if ui.button("Stop").clicked() {
+                            self.stop();
+                        }
+                        ui.spinner();
+                    } else if ui
+                        .add_enabled(file_data.is_some(), egui::Button::new("Extract"))
+                        .on_hover_text("List every run of printable text in the file")
+                        .clicked()
+                        && let Some(data) = file_data
+                    {
+                        self.start(data);
+                    }
+                });
+
+                if self.strings.is_empty() && !self.is_running() {
+                    ui.label("No strings listed");
+                    return;
+                }
+                let mut status = format!("{} strings", self.strings.len());
+                if self.limited {
+                    status.push_str(" (limit reached)");
+                }
+                ui.label(status);
+
+                TableBuilder::new(ui)
+                    .id_salt("StringsPanel.Table")
+                    .striped(true)
+                    .sense(egui::Sense::click())
+                    .cell_layout(egui::Layout::left_to_right(egui::Align::Center))
+                    .column(Column::exact(90.)) // Offset column
+                    .column(Column::remainder().clip(true)) // Text column
+                    .max_scroll_height(200.)
+                    .header(20.0, |mut header| {
+                        header.col(|ui| {
+                            ui.strong("Offset");
+                        });
+                        header.col(|ui| {
+                            ui.strong("Text");
+                        });
+                    })
+                    .body(|body| {
+                        body.rows(18.0, self.strings.len(), |mut row| {
+                            let found = &self.strings[row.index()];
+                            row.col(|ui| {
+                                // Not selectable, so a click on the text selects the row
+                                ui.add(
+                                    egui::Label::new(
+                                        egui::RichText::new(offset_format.format(found.offset))
+                                            .monospace(),
+                                    )
+                                    .selectable(false),
+                                );
+                            });
+                            row.col(|ui| {
+                                ui.add(
+                                    egui::Label::new(egui::RichText::new(&found.text).monospace())
+                                        .selectable(false),
+                                )
+                                .on_hover_text(format!("{} bytes", found.len));
+                            });
+                            let response = row.response();
+                            if response.clicked() {
+                                clicked = Some((found.offset, found.len));
+                            }
+                            response
+                                .context_menu(|ui| copy_offset(ui, found.offset, offset_format));
+                        });
+                    });
+            });
+        });
+
+        clicked
+    }
+}
@@ -0,0 +1,95 @@
+use crate::ui::components::data_inspector::Radix;
+use crate::ui::struct_template::{StructTemplate, TemplateError};
+use crate::ui::util::OffsetFormat;
+use eframe::egui;
+
+/// Decodes the bytes at the selection through a user-written struct template.
+pub struct StructPanel {
+    source: String,
+    little_endian: bool,
+    // Parsed from `source` whenever it is edited
+    template: Result<StructTemplate, TemplateError>,
+}
+
+impl Default for StructPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StructPanel {
+    const EXAMPLE: &str = "u32 magic;\nu16 version;\nu8 flags;";
+
+    pub fn new() -> Self {
+        Self {
+            source: Self::EXAMPLE.into(),
+            little_endian: true,
+            template: StructTemplate::parse(Self::EXAMPLE),
+        }
+    }
+
+    pub fn render(
+        &mut self,
+        ui: &mut egui::Ui,
+        selected_offset: Option<usize>,
+        file_data: Option<&[u8]>,
+        offset_base: OffsetFormat,
+    ) {
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Struct Template");
+                    ui.radio_value(&mut self.little_endian, true, "LE");
+                    ui.radio_value(&mut self.little_endian, false, "BE");
+                });
+
+                let resp = ui.add(
+                    egui::TextEdit::multiline(&mut self.source)
+                        .code_editor()
+                        .desired_rows(4)
+                        .desired_width(f32::INFINITY)
+                        .hint_text("u32 magic; u16 version[2];"),
+                );
+                if resp.changed() {
+                    self.template = StructTemplate::parse(&self.source);
+                }
+
+                let template = match &self.template {
+                    Ok(template) => template,
+                    Err(e) => {
+                        ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+                        return;
+                    }
+                };
+                let (Some(offset), Some(data)) = (selected_offset, file_data) else {
+                    ui.label("No selection");
+                    return;
+                };
+
+                let values = template.apply(
+                    data,
+                    offset,
+                    self.little_endian,
+                    Radix::Decimal,
+                    offset_base,
+                );
+                egui::ScrollArea::vertical()
+                    .id_salt("StructPanel.Scroll")
+                    .max_height(200.)
+                    .show(ui, |ui| {
+                        egui::Grid::new("StructPanel.Grid")
+                            .num_columns(3)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for value in values {
+                                    ui.monospace(offset_base.format(value.offset));
+                                    ui.label(value.name).on_hover_text(value.type_name);
+                                    ui.monospace(value.value);
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            });
+        });
+    }
+}
@@ -0,0 +1,82 @@
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastLevel {
+    Info,
+    Error,
+}
+
+struct Toast {
+    level: ToastLevel,
+    message: String,
+    created: Instant,
+}
+
+/// Transient notifications stacked in the bottom-right corner.
+/// Each one disappears after `LIFETIME` or when clicked.
+pub struct Toasts {
+    toasts: Vec<Toast>,
+}
+
+impl Default for Toasts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Toasts {
+    const LIFETIME: Duration = Duration::from_secs(5);
+
+    pub fn new() -> Self {
+        Self { toasts: Vec::new() }
+    }
+
+    pub fn push(&mut self, level: ToastLevel, message: impl Into<String>) {
+        self.toasts.push(Toast {
+            level,
+            message: message.into(),
+            created: Instant::now(),
+        });
+    }
+
+    pub fn render(&mut self, ctx: &egui::Context) {
+        self.toasts.retain(|t| t.created.elapsed() < Self::LIFETIME);
+        if self.toasts.is_empty() {
+            return;
+        }
+
+        let mut dismissed = None;
+        egui::Area::new(egui::Id::new("Toasts"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-12., -12.))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                for (i, toast) in self.toasts.iter().enumerate() {
+                    let fill = match toast.level {
+                        ToastLevel::Info => ui.visuals().extreme_bg_color,
+                        ToastLevel::Error => ui.visuals().error_fg_color.gamma_multiply(0.25),
+                    };
+                    let resp = egui::Frame::popup(ui.style())
+                        .fill(fill)
+                        .show(ui, |ui| {
+                            ui.set_max_width(360.);
+                            ui.label(&toast.message);
+                        })
+                        .response
+                        .interact(egui::Sense::click())
+                        .on_hover_text("Click to dismiss");
+                    if resp.clicked() {
+                        dismissed = Some(i);
+                    }
+                }
+            });
+        if let Some(i) = dismissed {
+            self.toasts.remove(i);
+        }
+
+        // Wake up again to expire the oldest toast even without input
+        if let Some(oldest) = self.toasts.iter().map(|t| t.created).min() {
+            ctx.request_repaint_after(Self::LIFETIME.saturating_sub(oldest.elapsed()));
+        }
+    }
+}
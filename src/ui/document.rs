@@ -0,0 +1,730 @@
+use crate::search::{self, AsyncSearch, AsyncStream, Endianness, NeedleOwned, SearchState};
+use crate::ui;
+use crate::ui::components::{
+    BookmarksPanel, ChecksumPanel, ComparePanel, FileData, FilePanel, HexViewer, HistogramPanel,
+    RunsPanel, SearchResultsPanel, SearchStatus, StringsPanel, ToastLevel, Toasts,
+};
+use crate::ui::util::{NavHistory, OffsetBase, OffsetFormat, Selection};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+pub enum CurrentSearch {
+    Empty,
+    Searching(usize, AsyncSearch),
+    Finished(usize),
+    // usize is the byte length of this search
+    Counting(usize, AsyncStream<usize>),
+    // usize is the number of matches counted so far + count handle
+    Counted(usize),
+    // usize is the number of matches of a count-only search
+    Limited(usize),
+    // usize is the byte length of a search stopped at the results limit
+}
+
+impl CurrentSearch {
+    pub fn take(&mut self) -> CurrentSearch {
+        let mut res = CurrentSearch::Empty;
+        std::mem::swap(&mut res, self);
+        res
+    }
+    pub fn status(&self) -> SearchStatus {
+        match self {
+            CurrentSearch::Empty => SearchStatus::Idle,
+            CurrentSearch::Searching(..) => SearchStatus::Searching,
+            CurrentSearch::Counting(n, _) => SearchStatus::Counting(*n),
+            CurrentSearch::Finished(_) => SearchStatus::Finished,
+            CurrentSearch::Counted(n) => SearchStatus::Counted(*n),
+            CurrentSearch::Limited(_) => SearchStatus::LimitReached,
+        }
+    }
+    pub fn byte_length(&self) -> Option<usize> {
+        match self {
+            CurrentSearch::Empty => None,
+            CurrentSearch::Searching(i, _) => Some(*i),
+            CurrentSearch::Finished(i) | CurrentSearch::Limited(i) => Some(*i),
+            CurrentSearch::Counting(..) | CurrentSearch::Counted(_) => None,
+        }
+    }
+    pub fn finish(&mut self) {
+        match self {
+            CurrentSearch::Empty => {}
+            CurrentSearch::Searching(i, _) => *self = CurrentSearch::Finished(*i),
+            CurrentSearch::Counting(n, _) => *self = CurrentSearch::Counted(*n),
+            CurrentSearch::Finished(_) | CurrentSearch::Counted(_) | CurrentSearch::Limited(_) => {}
+        }
+    }
+
+    /// Whether a worker thread is still searching or counting.
+    pub fn is_running(&self) -> bool {
+        matches!(self, CurrentSearch::Searching(..) | CurrentSearch::Counting(..))
+    }
+
+    /// Whether the search ran to an end and its results can be rescanned.
+    pub fn is_complete(&self) -> bool {
+        matches!(self, CurrentSearch::Finished(_) | CurrentSearch::Limited(_))
+    }
+}
+
+/// Where the user was in a file, as offsets into the file so that they still
+/// hold when a different window of it is loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilePosition {
+    // Lowest and highest selected offsets
+    selection: Option<(u64, u64)>,
+    top_offset: u64,
+}
+
+impl FilePosition {
+    /// The selection and the top offset in data starting at file offset
+    /// `origin`, each dropped when it is no longer in the data. A selection
+    /// running past the end is cut short instead.
+    fn resolve(&self, origin: usize, data_len: usize) -> (Option<Selection>, Option<usize>) {
+        let local = |offset: u64| {
+            offset
+                .checked_sub(origin as u64)
+                .and_then(|o| usize::try_from(o).ok())
+                .filter(|&o| o < data_len)
+        };
+        let selection = self.selection.and_then(|(lower, upper)| {
+            let lower = local(lower)?;
+            Some(Selection::range(
+                lower,
+                local(upper).unwrap_or(data_len - 1),
+            ))
+        });
+        (selection, local(self.top_offset))
+    }
+}
+
+/// The selection covering a `len`-byte match at `offset`, cut short at the
+/// end of `data_len` bytes, and whether it was cut. `offset` must be in the
+/// data. A zero length still selects the byte at `offset`, so the range
+/// stays valid however the match was made.
+fn match_selection(offset: usize, len: usize, data_len: usize) -> (Selection, bool) {
+    debug_assert!(offset < data_len);
+    let last = data_len - 1;
+    let end = offset.saturating_add(len.max(1) - 1);
+    (Selection::range(offset, end.min(last)), end > last)
+}
+
+/// The last position in recently closed files, by canonical path, most
+/// recent last. Persisted between runs.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FilePositions {
+    entries: Vec<(PathBuf, FilePosition)>,
+}
+
+impl FilePositions {
+    const MAX_ENTRIES: usize = 64;
+
+    fn remember(&mut self, key: PathBuf, position: FilePosition) {
+        self.entries.retain(|(k, _)| *k != key);
+        self.entries.push((key, position));
+        let excess = self.entries.len().saturating_sub(Self::MAX_ENTRIES);
+        self.entries.drain(..excess);
+    }
+
+    fn get(&self, key: &Path) -> Option<FilePosition> {
+        self.entries.iter().find(|(k, _)| k == key).map(|&(_, p)| p)
+    }
+}
+
+/// The values of an integer search in the other byte order, counted if the
+/// search finds nothing.
+pub struct EndiannessProbe {
+    pub endianness: Endianness,
+    pub needles: Vec<NeedleOwned>,
+    // Live searches only show the hint in the results, not a toast per keystroke
+    pub announce: bool,
+}
+
+/// An `EndiannessProbe` being counted on a worker thread.
+struct RunningProbe {
+    endianness: Endianness,
+    announce: bool,
+    counting: AsyncStream<usize>,
+    count: usize,
+}
+
+/// One open file and everything tied to its contents, shown as a tab.
+pub struct Document {
+    pub file_panel: FilePanel,
+    pub search_results_panel: SearchResultsPanel,
+    pub hex_viewer: HexViewer,
+    pub bookmarks_panel: BookmarksPanel,
+    pub checksum_panel: ChecksumPanel,
+    pub compare_panel: ComparePanel,
+    pub histogram_panel: HistogramPanel,
+    pub strings_panel: StringsPanel,
+    pub runs_panel: RunsPanel,
+    pub selection: Option<Selection>,
+    pub last_selection: Option<Selection>,
+    // Last search match selected, outlined while it is still the selection
+    pub active_match: Option<Selection>,
+    // Selections left behind by jumps, for Back/Forward
+    pub history: NavHistory,
+    // Index into the search results of the last result navigated to
+    pub current_result: Option<usize>,
+    // current_search: byte length + search handle
+    pub current_search: CurrentSearch,
+    // Needles of the last search, with the values they were parsed from, so
+    // a reload can run it again as it was
+    pub last_search: Vec<(String, NeedleOwned)>,
+    // Canonical path of the loaded file, which its position is stored under
+    position_key: Option<PathBuf>,
+    pub endianness_probe: Option<EndiannessProbe>,
+    // Offset the data inspector's "Offset (rel)" measures from in this file
+    pub relative_origin: Option<usize>,
+    running_probe: Option<RunningProbe>,
+}
+
+impl Default for Document {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Document {
+    pub fn new() -> Self {
+        Self {
+            file_panel: FilePanel::new(),
+            search_results_panel: SearchResultsPanel::new(),
+            hex_viewer: HexViewer::new(),
+            bookmarks_panel: BookmarksPanel::new(),
+            checksum_panel: ChecksumPanel::new(),
+            compare_panel: ComparePanel::new(),
+            histogram_panel: HistogramPanel::new(),
+            strings_panel: StringsPanel::new(),
+            runs_panel: RunsPanel::new(),
+            selection: None,
+            last_selection: None,
+            active_match: None,
+            history: NavHistory::default(),
+            current_result: None,
+            current_search: CurrentSearch::Empty,
+            last_search: Vec::new(),
+            position_key: None,
+            endianness_probe: None,
+            relative_origin: None,
+            running_probe: None,
+        }
+    }
+
+    /// File name for the tab, with a `*` while there are unsaved edits.
+    pub fn title(&self) -> String {
+        let parts = self.file_panel.get_parts();
+        let name = self
+            .file_panel
+            .get_file_path()
+            .as_ref()
+            .or(parts.and_then(|p| p.paths.first()))
+            .and_then(|p| p.file_name())
+            .map_or_else(
+                || "Untitled".to_string(),
+                |n| n.to_string_lossy().into_owned(),
+            );
+        // Joined files are named after the first, with a count of the rest
+        let name = match parts.map(|p| p.paths.len()) {
+            Some(count) if count > 1 => format!("{} (+{})", name, count - 1),
+            _ => name,
+        };
+        if self.file_panel.is_dirty() {
+            format!("{}*", name)
+        } else {
+            name
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.file_panel.get_file_data().is_none()
+    }
+
+    /// File was opened or reloaded, clear search results and cancel ongoing search.
+    /// The previous file's position is remembered in `positions`, and the new
+    /// file's restored from it.
+    pub fn on_file_opened(&mut self, positions: &mut FilePositions) {
+        self.switch_position_key(positions);
+        self.selection = None;
+        self.history.clear();
+        self.search_results_panel.clear_results();
+        self.current_result = None;
+        self.bookmarks_panel.clear_bookmarks();
+        self.compare_panel.clear();
+        self.strings_panel.clear();
+        self.hex_viewer.clear_delta_anchor();
+        self.relative_origin = None;
+        self.stop_search();
+        self.last_search.clear();
+
+        let Some(position) = self.position_key.as_ref().and_then(|k| positions.get(k)) else {
+            return;
+        };
+        let data_len = self.file_panel.get_file_data().map_or(0, <[u8]>::len);
+        let (selection, top) = position.resolve(self.file_panel.get_window_start(), data_len);
+        self.selection = selection;
+        // Counts as shown already, so the viewer keeps the restored scroll
+        // instead of jumping to the selection
+        self.last_selection = selection;
+        if let Some(top) = top {
+            self.hex_viewer.scroll_to_offset(top);
+        }
+    }
+
+    /// Called once the file panel loaded another file: stores the position
+    /// in the previous file under its path, then files later positions
+    /// under the path of the file now loaded.
+    pub fn switch_position_key(&mut self, positions: &mut FilePositions) {
+        self.remember_position(positions);
+        self.position_key = self
+            .file_panel
+            .get_file_path()
+            .as_ref()
+            .map(|path| path.canonicalize().unwrap_or_else(|_| path.clone()));
+    }
+
+    /// Stores the selection and scroll of the loaded file in `positions`.
+    pub fn remember_position(&self, positions: &mut FilePositions) {
+        let Some(key) = &self.position_key else {
+            return;
+        };
+        let origin = self.file_panel.get_window_start() as u64;
+        let position = FilePosition {
+            selection: self
+                .selection
+                .map(|s| (origin + s.lower() as u64, origin + s.upper() as u64)),
+            top_offset: origin + self.hex_viewer.top_offset() as u64,
+        };
+        positions.remember(key.clone(), position);
+    }
+
+    /// Cancels the search thread, if one is running, and forgets the search.
+    pub fn stop_search(&mut self) {
+        self.stop_endianness_probe();
+        let cancelled = match self.current_search.take() {
+            CurrentSearch::Searching(_, search) => search.cancel(),
+            CurrentSearch::Counting(_, counting) => counting.cancel(),
+            _ => Ok(()),
+        };
+        if let Err(e) = cancelled {
+            log::warn!("Search thread failed: {}", e);
+        }
+    }
+
+    /// Drops the results and stops the search producing them.
+    pub fn clear_search(&mut self) {
+        self.stop_search();
+        self.compare_panel.clear();
+        self.search_results_panel.clear_results();
+        self.current_result = None;
+        self.active_match = None;
+    }
+
+    /// Lists the runs of `byte` at least `min_len` long as the search
+    /// results, found on a worker thread like the matches of a search.
+    pub fn find_runs(&mut self, byte: u8, min_len: usize) {
+        self.clear_search();
+        // Not a search for a value, so there is nothing to search for again
+        // on reload or to rescan, and no byte order to probe
+        self.last_search.clear();
+        self.endianness_probe = None;
+        let Some(data) = self.file_panel.get_file_data_arc() else {
+            return;
+        };
+        self.search_results_panel.set_searched_for(
+            format!("runs of {:02X}, at least {} bytes", byte, min_len),
+            Vec::new(),
+        );
+        let search = AsyncSearch::create_runs(data, byte, min_len);
+        self.current_search = CurrentSearch::Searching(1, search);
+    }
+
+    /// Lists where the loaded data differs from the file at `path` as the
+    /// search results, found on a worker thread like the matches of a search.
+    pub fn compare_with(&mut self, path: PathBuf) -> std::io::Result<()> {
+        let Some(data) = self.file_panel.get_file_data_arc() else {
+            return Ok(());
+        };
+        let other = FileData::open(&path)?;
+        self.clear_search();
+        // Not a search for a value, as for `find_runs`
+        self.last_search.clear();
+        self.endianness_probe = None;
+        let name = path.file_name().unwrap_or(path.as_os_str()).to_string_lossy();
+        self.search_results_panel
+            .set_searched_for(format!("differences from {}", name), Vec::new());
+        let search = AsyncSearch::create_diff(data, other);
+        self.current_search = CurrentSearch::Searching(1, search);
+        self.compare_panel.start(path, self.file_panel.get_revision());
+        Ok(())
+    }
+
+    /// Drops the differences listed by `compare_with` once either file
+    /// changed, since their offsets may no longer be right.
+    pub fn drop_stale_comparison(&mut self, toasts: &mut Toasts) {
+        if self.compare_panel.is_stale(self.file_panel.get_revision()) {
+            self.clear_search();
+            toasts.push(
+                ToastLevel::Info,
+                format!("{} or the file compared with changed, comparison cleared", self.title()),
+            );
+        }
+    }
+
+    /// Stops a running search, keeping the results found so far.
+    pub fn cancel_search(&mut self, toasts: &mut Toasts) {
+        let (cancelled, stopped) = match self.current_search.take() {
+            CurrentSearch::Searching(len, search) => {
+                (search.cancel(), CurrentSearch::Finished(len))
+            }
+            CurrentSearch::Counting(n, counting) => (counting.cancel(), CurrentSearch::Counted(n)),
+            other => {
+                self.current_search = other;
+                return;
+            }
+        };
+        if let Err(e) = cancelled {
+            log::warn!("Search thread failed: {}", e);
+        }
+        self.current_search = stopped;
+        toasts.push(ToastLevel::Info, "Search cancelled");
+    }
+
+    /// Whether a search is running that the results panel still takes
+    /// matches from, or a count or endianness probe is still running, so
+    /// that frames must keep coming to collect them.
+    pub fn is_streaming(&self) -> bool {
+        match self.current_search {
+            CurrentSearch::Searching(..) => {
+                self.search_results_panel.get_search_results().len()
+                    < self.search_results_panel.get_result_limit()
+            }
+            CurrentSearch::Counting(..) => true,
+            _ => self.running_probe.is_some(),
+        }
+    }
+
+    /// Starts counting the matches of the search's values in the other byte
+    /// order, to suggest switching to it if there are any. Run once a search
+    /// has found nothing, since a wrong byte order finds nothing without a
+    /// hint. The count runs on a worker; `update_endianness_probe` shows
+    /// the hint when it is done.
+    pub fn probe_endianness(&mut self) {
+        let Some(probe) = self.endianness_probe.take() else {
+            return;
+        };
+        let Some(data) = self.file_panel.get_file_data_arc() else {
+            return;
+        };
+        self.stop_endianness_probe();
+        self.running_probe = Some(RunningProbe {
+            endianness: probe.endianness,
+            announce: probe.announce,
+            counting: search::count_async(data, probe.needles),
+            count: 0,
+        });
+    }
+
+    fn stop_endianness_probe(&mut self) {
+        if let Some(probe) = self.running_probe.take()
+            && let Err(e) = probe.counting.cancel()
+        {
+            log::warn!("Endianness probe thread failed: {}", e);
+        }
+    }
+
+    /// Adds up the running probe's count, and once it is done shows the
+    /// hint if the other byte order finds anything.
+    fn update_endianness_probe(&mut self, toasts: &mut Toasts) {
+        let Some(probe) = &mut self.running_probe else {
+            return;
+        };
+        match probe.counting.drain(|found| probe.count += found) {
+            SearchState::Pending => return,
+            SearchState::Finished => {}
+            // Only a hint, so a failed probe just shows none
+            SearchState::Panicked => {
+                self.stop_endianness_probe();
+                return;
+            }
+        }
+        let Some(RunningProbe {
+            endianness,
+            announce,
+            count,
+            ..
+        }) = self.running_probe.take()
+        else {
+            return;
+        };
+        if count == 0 {
+            return;
+        }
+        self.search_results_panel
+            .set_endianness_hint(count, endianness);
+        if !announce {
+            return;
+        }
+        let name = match endianness {
+            Endianness::BigEndian => "big-endian",
+            Endianness::LittleEndian => "little-endian",
+        };
+        toasts.push(
+            ToastLevel::Info,
+            format!(
+                "0 matches; found {} as {}, switch in the results",
+                count, name
+            ),
+        );
+    }
+
+    /// Shows offsets into the loaded bytes as offsets into the file.
+    pub fn offset_format(&self, base: OffsetBase) -> OffsetFormat {
+        OffsetFormat {
+            base,
+            origin: self.file_panel.get_window_start(),
+        }
+    }
+
+    /// Moves the selection somewhere new, remembering where it was for Back.
+    pub fn jump_to(&mut self, selection: Selection) {
+        if self.selection != Some(selection) {
+            self.history.visit(self.selection);
+            self.selection = Some(selection);
+        }
+    }
+
+    pub fn go_back(&mut self) {
+        if let Some(selection) = self.history.back(self.selection) {
+            self.selection = Some(selection);
+        }
+    }
+
+    pub fn go_forward(&mut self) {
+        if let Some(selection) = self.history.forward(self.selection) {
+            self.selection = Some(selection);
+        }
+    }
+
+    /// Offset the data inspector decodes from: the start of the selection.
+    pub fn inspected_offset(&self) -> Option<usize> {
+        self.selection.map(|s| s.lower())
+    }
+
+    /// Selects `len` bytes at `offset`, clamped to the file. The file may have
+    /// shrunk on reload since the match was found.
+    pub fn select_match(
+        &mut self,
+        offset: usize,
+        len: usize,
+        base: OffsetBase,
+        toasts: &mut Toasts,
+    ) {
+        let data_len = self.file_panel.get_file_data().map_or(0, <[u8]>::len);
+        if offset >= data_len {
+            toasts.push(
+                ToastLevel::Error,
+                format!(
+                    "Match at {} is past the end of the file",
+                    self.offset_format(base).format(offset)
+                ),
+            );
+            return;
+        }
+        let (selection, clamped) = match_selection(offset, len, data_len);
+        if clamped {
+            toasts.push(
+                ToastLevel::Info,
+                "Match runs past the end of the file, selection clamped",
+            );
+        }
+        self.jump_to(selection);
+        self.active_match = Some(selection);
+    }
+
+    pub fn goto_result(&mut self, index: usize, base: OffsetBase, toasts: &mut Toasts) {
+        let Some(&result) = self.search_results_panel.get_search_results().get(index) else {
+            return;
+        };
+        self.select_match(result.offset, result.len, base, toasts);
+        self.current_result = Some(index);
+        self.search_results_panel.set_focused_row(index);
+    }
+
+    /// Moves to the next (or previous) result, wrapping around at either end.
+    pub fn step_result(&mut self, forward: bool, base: OffsetBase, toasts: &mut Toasts) {
+        let count = self.search_results_panel.get_search_results().len();
+        if count == 0 {
+            return;
+        }
+        let index = match (self.current_result, forward) {
+            (None, true) => 0,
+            (None, false) => count - 1,
+            (Some(i), true) => (i + 1) % count,
+            (Some(i), false) => (i + count - 1) % count,
+        };
+        self.goto_result(index, base, toasts);
+    }
+
+    /// Adds up the partial counts of a count-only search, finishing it once
+    /// the worker is done.
+    fn update_count(&mut self, toasts: &mut Toasts) {
+        let CurrentSearch::Counting(total, counting) = &mut self.current_search else {
+            return;
+        };
+        match counting.drain(|found| *total += found) {
+            SearchState::Pending => {}
+            SearchState::Finished => {
+                self.current_search.finish();
+                if matches!(self.current_search, CurrentSearch::Counted(0)) {
+                    self.probe_endianness();
+                }
+            }
+            SearchState::Panicked => {
+                self.current_search.finish();
+                toasts.push(
+                    ToastLevel::Error,
+                    format!(
+                        "Count in {} failed unexpectedly, the count may be short",
+                        self.title()
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Moves new matches into the results panel, stopping the search once
+    /// `max_results` are held. A count-only search just adds up its count.
+    pub fn update_search_results(&mut self, max_results: usize, toasts: &mut Toasts) {
+        self.update_endianness_probe(toasts);
+        self.update_count(toasts);
+        if let CurrentSearch::Searching(len, search) = &self.current_search {
+            let len = *len;
+            let mut results = Vec::new();
+            let mut result_count = 0;
+
+            // Drain for a slice of the frame rather than a fixed count, so a
+            // flood of matches keeps the UI near 60fps on any machine, and
+            // leave the rest in the search until the panel asks for more
+            const DRAIN_BUDGET: Duration = Duration::from_millis(4);
+            const CLOCK_CHECK_INTERVAL: usize = 4096;
+            let held = self.search_results_panel.get_search_results().len();
+            let limit = self
+                .search_results_panel
+                .get_result_limit()
+                .min(max_results);
+            let wanted = limit.saturating_sub(held);
+            let started = Instant::now();
+
+            while result_count < wanted {
+                match search.try_get() {
+                    Ok(m) => {
+                        results.push(ui::SearchResult {
+                            index: result_count,
+                            offset: m.offset,
+                            len: m.len,
+                            pattern: m.pattern,
+                        });
+                        result_count += 1;
+
+                        if result_count.is_multiple_of(CLOCK_CHECK_INTERVAL)
+                            && started.elapsed() >= DRAIN_BUDGET
+                        {
+                            break;
+                        }
+                    }
+                    Err(SearchState::Pending) => {
+                        // No more results available right now
+                        break;
+                    }
+                    Err(SearchState::Finished) => {
+                        // Search is complete, remove it
+                        self.current_search.finish();
+                        break;
+                    }
+                    Err(SearchState::Panicked) => {
+                        self.current_search.finish();
+                        toasts.push(
+                            ToastLevel::Error,
+                            format!(
+                                "Search in {} failed unexpectedly, the results may be incomplete",
+                                self.title()
+                            ),
+                        );
+                        break;
+                    }
+                }
+            }
+
+            // Add new results to the panel
+            if !results.is_empty() {
+                self.search_results_panel.add_search_results(results);
+            }
+
+            // Stop a search that would otherwise grow the results without bound
+            if held + result_count >= max_results
+                && matches!(self.current_search, CurrentSearch::Searching(..))
+            {
+                self.stop_search();
+                self.current_search = CurrentSearch::Limited(len);
+            }
+            if held + result_count == 0 && matches!(self.current_search, CurrentSearch::Finished(_))
+            {
+                self.probe_endianness();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_position() {
+        let position = FilePosition {
+            selection: Some((0x110, 0x11F)),
+            top_offset: 0x100,
+        };
+        assert_eq!(
+            position.resolve(0, 0x200),
+            (Some(Selection::range(0x110, 0x11F)), Some(0x100))
+        );
+        // Offsets are into the file, wherever the loaded window starts
+        assert_eq!(
+            position.resolve(0x100, 0x100),
+            (Some(Selection::range(0x10, 0x1F)), Some(0))
+        );
+        // A file that shrank keeps what is still in it
+        assert_eq!(
+            position.resolve(0, 0x118),
+            (Some(Selection::range(0x110, 0x117)), Some(0x100))
+        );
+        assert_eq!(position.resolve(0, 0x100), (None, None));
+        assert_eq!(position.resolve(0x120, 0x100), (None, None));
+
+        let mut positions = FilePositions::default();
+        for i in 0..=FilePositions::MAX_ENTRIES {
+            positions.remember(PathBuf::from(i.to_string()), position);
+        }
+        assert_eq!(positions.entries.len(), FilePositions::MAX_ENTRIES);
+        assert_eq!(positions.get(Path::new("0")), None);
+        assert_eq!(positions.get(Path::new("1")), Some(position));
+    }
+
+    #[test]
+    fn test_match_selection() {
+        assert_eq!(match_selection(4, 2, 16), (Selection::range(4, 5), false));
+        assert_eq!(match_selection(4, 0, 16), (Selection::range(4, 4), false));
+        assert_eq!(match_selection(14, 4, 16), (Selection::range(14, 15), true));
+        assert_eq!(
+            match_selection(15, 1, 16),
+            (Selection::range(15, 15), false)
+        );
+        assert_eq!(
+            match_selection(15, usize::MAX, 16),
+            (Selection::range(15, 15), true)
+        );
+    }
+}
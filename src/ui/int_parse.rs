@@ -0,0 +1,520 @@
+use std::fmt;
+use std::num::{IntErrorKind, ParseIntError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputParseError {
+    Empty,
+    InvalidDigit,
+    MisplacedSeparator,
+    Overflow,
+    InvalidCharLiteral,
+    NonByteChar,
+    /// A token of a byte string that is not hex bytes; holds the token as typed.
+    InvalidHexToken(String),
+    /// A value of a byte list outside `0..=255`.
+    ByteOutOfRange(String),
+    /// A value of a byte list that is not a number.
+    InvalidByte(String),
+}
+
+impl fmt::Display for InputParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            InputParseError::Empty => "empty input",
+            InputParseError::InvalidDigit => "invalid digit",
+            InputParseError::MisplacedSeparator => "misplaced '_' separator",
+            InputParseError::Overflow => "value out of range",
+            InputParseError::InvalidCharLiteral => "invalid character literal",
+            InputParseError::NonByteChar => {
+                "character does not fit in one byte, search for it as a String instead"
+            }
+            InputParseError::InvalidHexToken(token) => {
+                return write!(f, "'{}' is not a hex byte", token);
+            }
+            InputParseError::ByteOutOfRange(token) => {
+                return write!(f, "'{}' is outside the byte range 0 to 255", token);
+            }
+            InputParseError::InvalidByte(token) => {
+                return write!(f, "'{}' is not a byte value", token);
+            }
+        };
+        f.write_str(msg)
+    }
+}
+
+impl std::error::Error for InputParseError {}
+
+impl From<ParseIntError> for InputParseError {
+    fn from(e: ParseIntError) -> Self {
+        match e.kind() {
+            IntErrorKind::Empty => InputParseError::Empty,
+            IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => InputParseError::Overflow,
+            _ => InputParseError::InvalidDigit,
+        }
+    }
+}
+
+/// Integer types `IntParser::parse` can produce.
+pub trait FromStrRadix: Sized {
+    fn from_str_radix(src: &str, radix: u32) -> Result<Self, ParseIntError>;
+    /// Value of a character literal; signed types reinterpret the byte.
+    fn from_byte(byte: u8) -> Self;
+}
+
+macro_rules! impl_from_str_radix {
+    ($($t:ty),*) => {
+        $(
+            impl FromStrRadix for $t {
+                fn from_str_radix(src: &str, radix: u32) -> Result<Self, ParseIntError> {
+                    <$t>::from_str_radix(src, radix)
+                }
+                fn from_byte(byte: u8) -> Self {
+                    byte as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_from_str_radix!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128);
+
+/// Parses values typed by the user. Every search type that takes numbers
+/// reads them the same way:
+///
+/// - a list is split by `split_values`, on commas and whitespace;
+/// - a number is decimal, or hexadecimal/octal/binary with a `0x`/`0o`/`0b`
+///   prefix (either case), optionally grouped with `_` separators;
+/// - a quoted character such as `'A'` or `'\n'` stands for its byte value.
+///
+/// Integer searches take one number or character per value. Byte strings take
+/// tokens of hex digit pairs, each with an optional `0x` prefix. Byte lists take
+/// one byte per token, read like an 8-bit integer. Text is taken as typed.
+pub struct IntParser;
+
+impl IntParser {
+    /// Returns `None` when `input` is not quoted and should be parsed as a number.
+    /// Accepts ASCII characters and the escapes `\n`, `\r`, `\t`, `\0`, `\\`,
+    /// `\'`, `\"` and `\xHH`.
+    fn parse_char_literal(input: &str) -> Option<Result<u8, InputParseError>> {
+        let input = input.trim();
+        if input.len() < 2 {
+            return None;
+        }
+        let inner = input.strip_prefix('\'')?.strip_suffix('\'')?;
+        let mut chars = inner.chars();
+        let byte = match chars.next() {
+            None => return Some(Err(InputParseError::Empty)),
+            Some('\\') => match chars.next() {
+                Some('n') => b'\n',
+                Some('r') => b'\r',
+                Some('t') => b'\t',
+                Some('0') => b'\0',
+                Some(c @ ('\\' | '\'' | '"')) => c as u8,
+                Some('x') => {
+                    let hex = chars.as_str();
+                    if hex.len() != 2 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                        return Some(Err(InputParseError::InvalidCharLiteral));
+                    }
+                    return Some(Ok(u8::from_str_radix(hex, 16).unwrap()));
+                }
+                _ => return Some(Err(InputParseError::InvalidCharLiteral)),
+            },
+            Some(c) if c.is_ascii() => c as u8,
+            Some(_) => return Some(Err(InputParseError::NonByteChar)),
+        };
+        if chars.next().is_some() {
+            return Some(Err(InputParseError::InvalidCharLiteral));
+        }
+        Some(Ok(byte))
+    }
+
+    /// Splits a list of values separated by commas or whitespace, keeping
+    /// quoted characters such as `','` and `' '` whole.
+    pub fn split_values(input: &str) -> Vec<&str> {
+        let mut values = Vec::new();
+        let mut start = None;
+        let mut quoted = false;
+        let mut escaped = false;
+        for (i, c) in input.char_indices() {
+            if quoted {
+                match c {
+                    _ if escaped => escaped = false,
+                    '\\' => escaped = true,
+                    '\'' => quoted = false,
+                    _ => {}
+                }
+            } else if c == ',' || c.is_whitespace() {
+                if let Some(s) = start.take() {
+                    values.push(&input[s..i]);
+                }
+            } else {
+                start.get_or_insert(i);
+                quoted = c == '\'';
+            }
+        }
+        if let Some(s) = start {
+            values.push(&input[s..]);
+        }
+        values
+    }
+
+    /// Splits a `0x`/`0o`/`0b` prefix, in either case, off `input`; no prefix is decimal.
+    fn split_radix_prefix(input: &str) -> (u32, &str) {
+        let lower = input.get(..2).map(str::to_ascii_lowercase);
+        match lower.as_deref() {
+            Some("0x") => (16, &input[2..]),
+            Some("0o") => (8, &input[2..]),
+            Some("0b") => (2, &input[2..]),
+            _ => (10, input),
+        }
+    }
+
+    /// Removes digit separators. A `_` must sit between two digits, so `_5`,
+    /// `5_` and `1__0` are rejected, as is `0x_FF` once the prefix is split off.
+    fn strip_separators(digits: &str) -> Result<String, InputParseError> {
+        let chars: Vec<char> = digits.chars().collect();
+        for (i, &c) in chars.iter().enumerate() {
+            if c != '_' {
+                continue;
+            }
+            let prev = i.checked_sub(1).map(|p| chars[p]);
+            let next = chars.get(i + 1).copied();
+            let is_digit = |c: Option<char>| c.is_some_and(|c| c.is_ascii_alphanumeric());
+            if !is_digit(prev) || !is_digit(next) {
+                return Err(InputParseError::MisplacedSeparator);
+            }
+        }
+
+        Ok(chars.into_iter().filter(|&c| c != '_').collect())
+    }
+
+    /// Splits the radix prefix off `input` and removes digit separators.
+    fn parse_base_and_number(input: &str) -> Result<(u32, String), InputParseError> {
+        let input = input.trim();
+        // A sign goes before the prefix, as in `-0x80`; it is handed on to
+        // `from_str_radix`, which rejects it for unsigned types
+        let (sign, input) = match input.strip_prefix(['-', '+']) {
+            Some(rest) => (&input[..1], rest),
+            None => ("", input),
+        };
+        let (radix, digits) = Self::split_radix_prefix(input);
+        let digits = Self::strip_separators(digits)?;
+        Ok((radix, format!("{}{}", sign, digits)))
+    }
+
+    /// Parses `input` as any primitive integer type. The radix prefix,
+    /// separators and character literals are handled here once for all of them.
+    pub fn parse<T: FromStrRadix>(input: &str) -> Result<T, InputParseError> {
+        if let Some(byte) = Self::parse_char_literal(input) {
+            return byte.map(T::from_byte);
+        }
+        let (radix, digits) = Self::parse_base_and_number(input)?;
+        Ok(T::from_str_radix(&digits, radix)?)
+    }
+
+    /// Parses a byte string such as `41 42`, `0x41, 0x42`, `4142` or `DEAD_BEEF`.
+    /// Each token may carry a `0x` prefix and holds whole bytes as pairs of hex
+    /// digits; a single digit stands for a byte on its own.
+    pub fn parse_hex_bytes(input: &str) -> Result<Vec<u8>, InputParseError> {
+        let mut bytes = Vec::new();
+        for token in Self::split_values(input) {
+            let invalid = || InputParseError::InvalidHexToken(token.to_string());
+            // Only `0x` is a prefix here; `0B` and `0b0c` are hex bytes
+            let digits = token
+                .strip_prefix("0x")
+                .or_else(|| token.strip_prefix("0X"))
+                .unwrap_or(token);
+            let digits = Self::strip_separators(digits).map_err(|_| invalid())?;
+            if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(invalid());
+            }
+            if digits.len() == 1 {
+                bytes.push(u8::from_str_radix(&digits, 16).unwrap());
+                continue;
+            }
+            if !digits.len().is_multiple_of(2) {
+                return Err(invalid());
+            }
+            for pair in digits.as_bytes().chunks(2) {
+                // Pairs of ASCII hex digits are always valid UTF-8 and a valid byte
+                let pair = std::str::from_utf8(pair).unwrap();
+                bytes.push(u8::from_str_radix(pair, 16).unwrap());
+            }
+        }
+        if bytes.is_empty() {
+            return Err(InputParseError::Empty);
+        }
+        Ok(bytes)
+    }
+
+    /// Parses a list of byte values such as `72 101 108` or `72, 101, 'l'`,
+    /// each read like any other 8-bit input, so decimal unless prefixed.
+    pub fn parse_dec_bytes(input: &str) -> Result<Vec<u8>, InputParseError> {
+        let bytes = Self::split_values(input)
+            .into_iter()
+            .map(|token| {
+                Self::parse_u8(token).map_err(|e| match e {
+                    InputParseError::Overflow => InputParseError::ByteOutOfRange(token.to_string()),
+                    _ => InputParseError::InvalidByte(token.to_string()),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if bytes.is_empty() {
+            return Err(InputParseError::Empty);
+        }
+        Ok(bytes)
+    }
+
+    pub fn parse_u8(input: &str) -> Result<u8, InputParseError> {
+        Self::parse(input)
+    }
+
+    pub fn parse_i8(input: &str) -> Result<i8, InputParseError> {
+        Self::parse(input)
+    }
+
+    pub fn parse_u16(input: &str) -> Result<u16, InputParseError> {
+        Self::parse(input)
+    }
+
+    pub fn parse_i16(input: &str) -> Result<i16, InputParseError> {
+        Self::parse(input)
+    }
+
+    pub fn parse_u32(input: &str) -> Result<u32, InputParseError> {
+        Self::parse(input)
+    }
+
+    pub fn parse_i32(input: &str) -> Result<i32, InputParseError> {
+        Self::parse(input)
+    }
+
+    pub fn parse_u64(input: &str) -> Result<u64, InputParseError> {
+        Self::parse(input)
+    }
+
+    pub fn parse_i64(input: &str) -> Result<i64, InputParseError> {
+        Self::parse(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_values() {
+        assert_eq!(
+            IntParser::split_values("100, 200,0x1F4  -5"),
+            ["100", "200", "0x1F4", "-5"]
+        );
+        assert_eq!(
+            IntParser::split_values("' ', ',' '\\''"),
+            ["' '", "','", "'\\''"]
+        );
+        assert_eq!(IntParser::split_values("42"), ["42"]);
+        assert!(IntParser::split_values(" , ").is_empty());
+    }
+
+    #[test]
+    fn test_parse_prefixes() {
+        assert_eq!(IntParser::parse_u32("123"), Ok(123));
+        assert_eq!(IntParser::parse_u32("0x1A2B"), Ok(0x1A2B));
+        assert_eq!(IntParser::parse_u32("0XFF"), Ok(0xFF));
+        assert_eq!(IntParser::parse_u32("0o777"), Ok(0o777));
+        assert_eq!(IntParser::parse_u32("0b1010"), Ok(0b1010));
+        assert_eq!(IntParser::parse_i32("-456"), Ok(-456));
+        assert_eq!(IntParser::parse_u8("  42 "), Ok(42));
+    }
+
+    #[test]
+    fn test_parse_boundaries() {
+        assert_eq!(IntParser::parse_u8("255"), Ok(u8::MAX));
+        assert_eq!(IntParser::parse_u8("256"), Err(InputParseError::Overflow));
+        assert_eq!(IntParser::parse_i8("-128"), Ok(i8::MIN));
+        assert_eq!(IntParser::parse_i8("128"), Err(InputParseError::Overflow));
+        assert_eq!(IntParser::parse_i8("-129"), Err(InputParseError::Overflow));
+        assert_eq!(IntParser::parse_u16("0xFFFF"), Ok(u16::MAX));
+        assert_eq!(
+            IntParser::parse_u16("0x10000"),
+            Err(InputParseError::Overflow)
+        );
+        assert_eq!(IntParser::parse_i16("-32768"), Ok(i16::MIN));
+        assert_eq!(
+            IntParser::parse_u32("4294967296"),
+            Err(InputParseError::Overflow)
+        );
+        assert_eq!(IntParser::parse_i32("2147483647"), Ok(i32::MAX));
+        assert_eq!(IntParser::parse_u64("0xFFFFFFFFFFFFFFFF"), Ok(u64::MAX));
+        assert_eq!(
+            IntParser::parse_i64("9223372036854775808"),
+            Err(InputParseError::Overflow)
+        );
+        assert_eq!(
+            IntParser::parse_u8("-1"),
+            Err(InputParseError::InvalidDigit)
+        );
+    }
+
+    #[test]
+    fn test_parse_negative_prefixed() {
+        assert_eq!(IntParser::parse_i8("-0x80"), Ok(i8::MIN));
+        assert_eq!(IntParser::parse_i8("-0x81"), Err(InputParseError::Overflow));
+        assert_eq!(IntParser::parse_i16("-0o10"), Ok(-8));
+        assert_eq!(IntParser::parse_i32("-0b101"), Ok(-5));
+        assert_eq!(
+            IntParser::parse_i64("-0X_FF"),
+            Err(InputParseError::MisplacedSeparator)
+        );
+        assert_eq!(IntParser::parse_i64("-0xFF_FF"), Ok(-0xFFFF));
+        assert_eq!(IntParser::parse_i32("+0x10"), Ok(16));
+        assert_eq!(
+            IntParser::parse_u8("-0x10"),
+            Err(InputParseError::InvalidDigit)
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(IntParser::parse_u8(""), Err(InputParseError::Empty));
+        assert_eq!(IntParser::parse_u8("0x"), Err(InputParseError::Empty));
+        assert_eq!(
+            IntParser::parse_u8("12a"),
+            Err(InputParseError::InvalidDigit)
+        );
+        assert_eq!(
+            IntParser::parse_u8("0b102"),
+            Err(InputParseError::InvalidDigit)
+        );
+        assert_eq!(
+            IntParser::parse_u8("0o8"),
+            Err(InputParseError::InvalidDigit)
+        );
+    }
+
+    #[test]
+    fn test_parse_separators() {
+        assert_eq!(IntParser::parse_u32("1_000_000"), Ok(1_000_000));
+        assert_eq!(IntParser::parse_u32("0xDEAD_BEEF"), Ok(0xDEAD_BEEF));
+        assert_eq!(IntParser::parse_u8("0b1111_0000"), Ok(0b1111_0000));
+        assert_eq!(
+            IntParser::parse_i64("-9_223_372_036_854_775_808"),
+            Ok(i64::MIN)
+        );
+        assert_eq!(
+            IntParser::parse_u16("6_5536"),
+            Err(InputParseError::Overflow)
+        );
+
+        for bad in ["_5", "5_", "1__0", "0x_FF", "0xFF_", "_", "-_5"] {
+            assert_eq!(
+                IntParser::parse_i32(bad),
+                Err(InputParseError::MisplacedSeparator),
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_char_literals() {
+        assert_eq!(IntParser::parse_u8("'A'"), Ok(b'A'));
+        assert_eq!(IntParser::parse_u8(r"'\n'"), Ok(b'\n'));
+        assert_eq!(IntParser::parse_u8(r"'\t'"), Ok(b'\t'));
+        assert_eq!(IntParser::parse_u8(r"'\0'"), Ok(0));
+        assert_eq!(IntParser::parse_u8(r"'\''"), Ok(b'\''));
+        assert_eq!(IntParser::parse_u8(r"'\x41'"), Ok(0x41));
+        assert_eq!(IntParser::parse_i8(r"'\xFF'"), Ok(-1));
+        assert_eq!(IntParser::parse_u32("'z'"), Ok(0x7A));
+        assert_eq!(IntParser::parse_u8("'''"), Ok(b'\''));
+
+        assert_eq!(IntParser::parse_u8("''"), Err(InputParseError::Empty));
+        assert_eq!(
+            IntParser::parse_u8("'é'"),
+            Err(InputParseError::NonByteChar)
+        );
+        for bad in ["'AB'", r"'\q'", r"'\x4'", r"'\x4G'", r"'\x414'"] {
+            assert_eq!(
+                IntParser::parse_u8(bad),
+                Err(InputParseError::InvalidCharLiteral),
+                "{bad}"
+            );
+        }
+        assert_eq!(
+            IntParser::parse_u8("'A"),
+            Err(InputParseError::InvalidDigit)
+        );
+    }
+
+    #[test]
+    fn test_parse_hex_bytes() {
+        assert_eq!(IntParser::parse_hex_bytes("41 42"), Ok(vec![0x41, 0x42]));
+        assert_eq!(
+            IntParser::parse_hex_bytes("0x41 0x42"),
+            Ok(vec![0x41, 0x42])
+        );
+        assert_eq!(IntParser::parse_hex_bytes("4142"), Ok(vec![0x41, 0x42]));
+        assert_eq!(
+            IntParser::parse_hex_bytes(" 0XdeAD  7 "),
+            Ok(vec![0xDE, 0xAD, 0x07])
+        );
+        // Separated and grouped like integers
+        assert_eq!(
+            IntParser::parse_hex_bytes("0x41,42, DE_AD"),
+            Ok(vec![0x41, 0x42, 0xDE, 0xAD])
+        );
+
+        assert_eq!(
+            IntParser::parse_hex_bytes("  "),
+            Err(InputParseError::Empty)
+        );
+        // Hex that starts like a binary or octal prefix is still hex
+        assert_eq!(IntParser::parse_hex_bytes("0B"), Ok(vec![0x0B]));
+        assert_eq!(IntParser::parse_hex_bytes("0b 0c"), Ok(vec![0x0B, 0x0C]));
+        assert_eq!(IntParser::parse_hex_bytes("0BAD"), Ok(vec![0x0B, 0xAD]));
+        for (input, token) in [
+            ("b0 xx", "xx"),
+            ("41 0x", "0x"),
+            ("414", "414"),
+            ("41 4g", "4g"),
+            ("410x42", "410x42"),
+            ("-1", "-1"),
+            ("DE__AD", "DE__AD"),
+            ("0x_41", "0x_41"),
+        ] {
+            assert_eq!(
+                IntParser::parse_hex_bytes(input),
+                Err(InputParseError::InvalidHexToken(token.to_string())),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_dec_bytes() {
+        assert_eq!(
+            IntParser::parse_dec_bytes("72 101 108 108 111"),
+            Ok(b"Hello".to_vec())
+        );
+        assert_eq!(
+            IntParser::parse_dec_bytes("0, 255,16"),
+            Ok(vec![0, 255, 16])
+        );
+        // Prefixes and character literals read as in integer searches
+        assert_eq!(
+            IntParser::parse_dec_bytes("0x41 ',' ' ' 0b1"),
+            Ok(vec![0x41, b',', b' ', 1])
+        );
+        assert_eq!(
+            IntParser::parse_dec_bytes(" , "),
+            Err(InputParseError::Empty)
+        );
+        assert_eq!(
+            IntParser::parse_dec_bytes("1 256"),
+            Err(InputParseError::ByteOutOfRange("256".to_string()))
+        );
+        assert_eq!(
+            IntParser::parse_dec_bytes("1 -1"),
+            Err(InputParseError::InvalidByte("-1".to_string()))
+        );
+    }
+}
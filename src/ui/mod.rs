@@ -1,5 +1,8 @@
 pub mod app;
 pub mod util;
+pub mod int_parse;
+pub mod struct_template;
+pub mod document;
 pub mod components;
 
 pub use app::BinarySearchApp;
@@ -0,0 +1,271 @@
+use std::fmt;
+
+use crate::ui::components::data_inspector::{DataInspector, InspectorRow, Radix};
+use crate::ui::int_parse::IntParser;
+use crate::ui::util::OffsetFormat;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    Empty,
+    /// A field type that is not one of the decodable types; holds the type as written.
+    UnknownType(String),
+    /// A field declaration without exactly a type and a name; holds the declaration.
+    InvalidField(String),
+    /// An array length that is not a number from 1 to `MAX_ARRAY_LEN`.
+    InvalidArrayLength(String),
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::Empty => write!(f, "the template has no fields"),
+            TemplateError::UnknownType(ty) => write!(f, "unknown type '{}'", ty),
+            TemplateError::InvalidField(field) => {
+                write!(f, "'{}' is not a field, expected `type name;`", field)
+            }
+            TemplateError::InvalidArrayLength(len) => write!(
+                f,
+                "'{}' is not an array length from 1 to {}",
+                len,
+                StructTemplate::MAX_ARRAY_LEN
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+struct Field {
+    name: String,
+    type_name: String,
+    row: InspectorRow,
+    size: usize,
+    // 1 for a plain field, the element count for an array
+    count: usize,
+}
+
+/// One decoded field, or one element of an array field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldValue {
+    pub offset: usize,
+    pub name: String,
+    pub type_name: String,
+    pub value: String,
+}
+
+/// A list of fields laid out back to back, written like a C struct body:
+///
+/// ```text
+/// u32 magic;
+/// u16 version;
+/// u8 flags; u8 reserved[3]; // comments run to the end of the line
+/// ```
+pub struct StructTemplate {
+    fields: Vec<Field>,
+}
+
+impl StructTemplate {
+    pub const MAX_ARRAY_LEN: usize = 4096;
+
+    /// Decoder row and byte size of the type called `name`.
+    fn field_type(name: &str) -> Option<(InspectorRow, usize)> {
+        use InspectorRow::*;
+        let ty = match name {
+            "u8" => (U8, 1),
+            "i8" => (I8, 1),
+            "u16" => (U16, 2),
+            "i16" => (I16, 2),
+            "u24" => (U24, 3),
+            "i24" => (I24, 3),
+            "u32" => (U32, 4),
+            "i32" => (I32, 4),
+            "u64" => (U64, 8),
+            "i64" => (I64, 8),
+            "u128" => (U128, 16),
+            "i128" => (I128, 16),
+            "f16" => (F16, 2),
+            "bf16" => (Bf16, 2),
+            "f32" => (F32, 4),
+            "f64" => (F64, 8),
+            "time_t32" => (Unix32, 4),
+            "time_t64" => (Unix64, 8),
+            "time_ms64" => (Unix64Millis, 8),
+            "filetime" => (FileTime, 8),
+            "dostime" => (DosDateTime, 4),
+            "ptr32" => (Ptr32, 4),
+            "ptr64" => (Ptr64, 8),
+            _ => return None,
+        };
+        Some(ty)
+    }
+
+    /// Parses fields of the form `type name;` or `type name[len];`.
+    pub fn parse(src: &str) -> Result<Self, TemplateError> {
+        let mut fields = Vec::new();
+        let code = src
+            .lines()
+            .map(|line| line.split("//").next().unwrap_or_default())
+            .collect::<Vec<_>>()
+            .join("\n");
+        for decl in code.split([';', '\n']).map(str::trim) {
+            if decl.is_empty() {
+                continue;
+            }
+            let tokens: Vec<&str> = decl.split_whitespace().collect();
+            let [type_name, name] = tokens[..] else {
+                return Err(TemplateError::InvalidField(decl.to_string()));
+            };
+            let (row, size) = Self::field_type(type_name)
+                .ok_or_else(|| TemplateError::UnknownType(type_name.to_string()))?;
+            let (name, count) = match name.split_once('[') {
+                None => (name, 1),
+                Some((name, len)) => {
+                    let len = len.strip_suffix(']').unwrap_or(len);
+                    let count = IntParser::parse::<u64>(len)
+                        .ok()
+                        .and_then(|n| usize::try_from(n).ok())
+                        .filter(|n| (1..=Self::MAX_ARRAY_LEN).contains(n))
+                        .ok_or_else(|| TemplateError::InvalidArrayLength(len.to_string()))?;
+                    (name, count)
+                }
+            };
+            if name.is_empty() {
+                return Err(TemplateError::InvalidField(decl.to_string()));
+            }
+            fields.push(Field {
+                name: name.to_string(),
+                type_name: type_name.to_string(),
+                row,
+                size,
+                count,
+            });
+        }
+        if fields.is_empty() {
+            return Err(TemplateError::Empty);
+        }
+        Ok(Self { fields })
+    }
+
+    /// Total byte size of the fields.
+    pub fn size(&self) -> usize {
+        self.fields.iter().map(|f| f.size * f.count).sum()
+    }
+
+    /// Decodes the fields laid out from `offset` in `data`. Fields past the
+    /// end of the data read as `DataInspector::EOF_MSG`.
+    pub fn apply(
+        &self,
+        data: &[u8],
+        offset: usize,
+        little_endian: bool,
+        radix: Radix,
+        offset_base: OffsetFormat,
+    ) -> Vec<FieldValue> {
+        let mut values = Vec::new();
+        let mut at = offset;
+        for field in &self.fields {
+            for i in 0..field.count {
+                let bytes = data
+                    .get(at..at.saturating_add(field.size))
+                    .unwrap_or_default();
+                let name = if field.count == 1 {
+                    field.name.clone()
+                } else {
+                    format!("{}[{}]", field.name, i)
+                };
+                values.push(FieldValue {
+                    offset: at,
+                    name,
+                    type_name: field.type_name.clone(),
+                    value: DataInspector::decode(
+                        field.row,
+                        bytes,
+                        radix,
+                        little_endian,
+                        data.len(),
+                        offset_base,
+                    ),
+                });
+                at = at.saturating_add(field.size);
+            }
+        }
+        values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::util::OffsetBase;
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(StructTemplate::parse("").is_err());
+        assert_eq!(
+            StructTemplate::parse("// nothing here").err(),
+            Some(TemplateError::Empty)
+        );
+        assert_eq!(
+            StructTemplate::parse("u33 x;").err(),
+            Some(TemplateError::UnknownType("u33".into()))
+        );
+        assert_eq!(
+            StructTemplate::parse("u32;").err(),
+            Some(TemplateError::InvalidField("u32".into()))
+        );
+        assert_eq!(
+            StructTemplate::parse("u8 pad[0];").err(),
+            Some(TemplateError::InvalidArrayLength("0".into()))
+        );
+    }
+
+    #[test]
+    fn test_apply() {
+        let template = StructTemplate::parse(
+            "u32 magic; // file signature\nu16 version;\nu8 flags[2]; u32 size;",
+        )
+        .unwrap();
+        assert_eq!(template.size(), 12);
+
+        let data = [0x7F, b'E', b'L', b'F', 0x02, 0x01, 0xAA, 0xBB, 0x00];
+        let values = template.apply(
+            &data,
+            0,
+            false,
+            Radix::Hexadecimal,
+            OffsetBase::Hexadecimal.into(),
+        );
+        let rows: Vec<(usize, &str)> = values.iter().map(|v| (v.offset, v.name.as_str())).collect();
+        assert_eq!(
+            rows,
+            [
+                (0, "magic"),
+                (4, "version"),
+                (6, "flags[0]"),
+                (7, "flags[1]"),
+                (8, "size")
+            ]
+        );
+        assert_eq!(values[4].value, DataInspector::EOF_MSG);
+
+        // The same bytes read little-endian give a different version
+        let le = template.apply(
+            &data,
+            0,
+            true,
+            Radix::Hexadecimal,
+            OffsetBase::Hexadecimal.into(),
+        );
+        assert_ne!(le[1].value, values[1].value);
+
+        // Starting past the end reads every field as missing
+        let past = template.apply(
+            &data,
+            100,
+            true,
+            Radix::Decimal,
+            OffsetBase::Hexadecimal.into(),
+        );
+        assert!(past.iter().all(|v| v.value == DataInspector::EOF_MSG));
+    }
+}
@@ -1,13 +1,16 @@
+use eframe::egui;
+use serde::{Deserialize, Serialize};
 use strum_macros::EnumIter;
 
 #[non_exhaustive]
-#[derive(Clone, Copy, PartialEq, EnumIter)]
+#[derive(Clone, Copy, PartialEq, EnumIter, Serialize, Deserialize)]
 pub enum SearchType {
     Bit8,
     Bit16,
     Bit32,
     Bit64,
     Bytes,
+    DecBytes,
     String,
 }
 
@@ -19,6 +22,7 @@ impl std::fmt::Display for SearchType {
             SearchType::Bit32 => write!(f, "32-Bit"),
             SearchType::Bit64 => write!(f, "64-Bit"),
             SearchType::Bytes => write!(f, "Bytes"),
+            SearchType::DecBytes => write!(f, "Dec Bytes"),
             SearchType::String => write!(f, "String"),
         }
     }
@@ -44,18 +48,230 @@ impl SearchType {
     }
 }
 #[non_exhaustive]
-#[derive(Debug, Clone, Copy, PartialEq, EnumIter)]
+#[derive(Debug, Clone, Copy, PartialEq, EnumIter, Serialize, Deserialize)]
 pub enum Encoding {
     UTF8,
+    Latin1,
+    Windows1252,
+    Utf16Le,
+    Utf16Be,
+    /// Needs the `cjk` feature for anything but ASCII.
+    ShiftJis,
+    /// Needs the `cjk` feature for anything but ASCII.
+    EucJp,
     /* ... */
 }
 
+/// Windows-1252 characters for bytes 0x80–0x9F, `None` where unmapped
+/// (0x81, 0x8D, 0x8F, 0x90, 0x9D). Other bytes match Latin-1.
+pub const WIN1252_80_9F: [Option<char>; 32] = [
+    Some('€'),
+    None,
+    Some('‚'),
+    Some('ƒ'),
+    Some('„'),
+    Some('…'),
+    Some('†'),
+    Some('‡'),
+    Some('ˆ'),
+    Some('‰'),
+    Some('Š'),
+    Some('‹'),
+    Some('Œ'),
+    None,
+    Some('Ž'),
+    None,
+    None,
+    Some('‘'),
+    Some('’'),
+    Some('“'),
+    Some('”'),
+    Some('•'),
+    Some('–'),
+    Some('—'),
+    Some('˜'),
+    Some('™'),
+    Some('š'),
+    Some('›'),
+    Some('œ'),
+    None,
+    Some('ž'),
+    Some('Ÿ'),
+];
+
+impl Encoding {
+    /// Encodes `s` for searching, or returns the first character this
+    /// encoding cannot represent.
+    pub fn encode(&self, s: &str) -> Result<Vec<u8>, char> {
+        match self {
+            Encoding::UTF8 => Ok(s.as_bytes().to_vec()),
+            Encoding::Latin1 => s.chars().map(|c| u8::try_from(c).map_err(|_| c)).collect(),
+            Encoding::Windows1252 => s
+                .chars()
+                .map(|c| match u8::try_from(c) {
+                    Ok(b) if !(0x80..=0x9F).contains(&b) => Ok(b),
+                    _ => WIN1252_80_9F
+                        .iter()
+                        .position(|&m| m == Some(c))
+                        .map(|i| 0x80 + i as u8)
+                        .ok_or(c),
+                })
+                .collect(),
+            // Astral characters become surrogate pairs
+            Encoding::Utf16Le => Ok(s.encode_utf16().flat_map(u16::to_le_bytes).collect()),
+            Encoding::Utf16Be => Ok(s.encode_utf16().flat_map(u16::to_be_bytes).collect()),
+            #[cfg(feature = "cjk")]
+            Encoding::ShiftJis => Self::encode_cjk(encoding_rs::SHIFT_JIS, s),
+            #[cfg(feature = "cjk")]
+            Encoding::EucJp => Self::encode_cjk(encoding_rs::EUC_JP, s),
+            // Both leave ASCII as it is
+            #[cfg(not(feature = "cjk"))]
+            Encoding::ShiftJis | Encoding::EucJp => match s.chars().find(|c| !c.is_ascii()) {
+                Some(c) => Err(c),
+                None => Ok(s.as_bytes().to_vec()),
+            },
+        }
+    }
+
+    #[cfg(feature = "cjk")]
+    fn encode_cjk(encoding: &'static encoding_rs::Encoding, s: &str) -> Result<Vec<u8>, char> {
+        let (bytes, _, unmappable) = encoding.encode(s);
+        if !unmappable {
+            return Ok(bytes.into_owned());
+        }
+        // Unmappable characters were written as HTML character references;
+        // find the first one to report instead
+        let mut buf = [0; 4];
+        Err(s
+            .chars()
+            .find(|c| encoding.encode(c.encode_utf8(&mut buf)).2)
+            .unwrap_or_default())
+    }
+
+    /// Whether this build can encode more than ASCII in this encoding.
+    pub fn is_available(&self) -> bool {
+        !matches!(self, Encoding::ShiftJis | Encoding::EucJp) || cfg!(feature = "cjk")
+    }
+
+    /// Byte order mark that starts text in this encoding, if it has one.
+    pub fn bom(&self) -> Option<&'static [u8]> {
+        match self {
+            Encoding::UTF8 => Some(&[0xEF, 0xBB, 0xBF]),
+            Encoding::Utf16Le => Some(&[0xFF, 0xFE]),
+            Encoding::Utf16Be => Some(&[0xFE, 0xFF]),
+            Encoding::Latin1 | Encoding::Windows1252 | Encoding::ShiftJis | Encoding::EucJp => None,
+        }
+    }
+
+    /// Encoding announced by a byte order mark at the start of `data`.
+    pub fn detect_bom(data: &[u8]) -> Option<Self> {
+        [Encoding::UTF8, Encoding::Utf16Le, Encoding::Utf16Be]
+            .into_iter()
+            .find(|e| e.bom().is_some_and(|bom| data.starts_with(bom)))
+    }
+}
+
 impl std::fmt::Display for Encoding {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            Encoding::ShiftJis => write!(f, "Shift-JIS"),
+            Encoding::EucJp => write!(f, "EUC-JP"),
+            _ => write!(f, "{:?}", self),
+        }
+    }
+}
+
+/// How file offsets are written out, independent of the data inspector's `Radix`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+pub enum OffsetBase {
+    Hexadecimal,
+    Decimal,
+}
+
+impl std::fmt::Display for OffsetBase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OffsetBase::Hexadecimal => write!(f, "Hex"),
+            OffsetBase::Decimal => write!(f, "Dec"),
+        }
+    }
+}
+
+impl OffsetBase {
+    pub fn other(&self) -> Self {
+        match self {
+            OffsetBase::Hexadecimal => OffsetBase::Decimal,
+            OffsetBase::Decimal => OffsetBase::Hexadecimal,
+        }
+    }
+
+    /// Formats an offset for display next to other text, e.g. `0x0000001F` or `31`.
+    pub fn format(&self, offset: usize) -> String {
+        match self {
+            OffsetBase::Hexadecimal => format!("0x{:08X}", offset),
+            OffsetBase::Decimal => format!("{}", offset),
+        }
+    }
+
+    /// Formats an offset as a fixed-width column entry.
+    pub fn format_column(&self, offset: usize) -> String {
+        match self {
+            OffsetBase::Hexadecimal => format!("{:08X}", offset),
+            OffsetBase::Decimal => format!("{:010}", offset),
+        }
+    }
+}
+
+/// An `OffsetBase` plus where the loaded bytes start in the file, so that
+/// offsets into an opened window are shown as offsets into the whole file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OffsetFormat {
+    pub base: OffsetBase,
+    pub origin: usize,
+}
+
+impl From<OffsetBase> for OffsetFormat {
+    fn from(base: OffsetBase) -> Self {
+        Self { base, origin: 0 }
+    }
+}
+
+impl OffsetFormat {
+    pub fn other(&self) -> Self {
+        Self {
+            base: self.base.other(),
+            origin: self.origin,
+        }
+    }
+
+    /// Formats an offset into the loaded bytes as a file offset.
+    pub fn format(&self, offset: usize) -> String {
+        self.base.format(self.origin + offset)
+    }
+
+    pub fn format_column(&self, offset: usize) -> String {
+        self.base.format_column(self.origin + offset)
     }
 }
 
+/// Context menu entries that copy `offset` as a file offset, in `format`'s
+/// base and then in the other one, for every view that shows offsets.
+pub fn copy_offset(ui: &mut egui::Ui, offset: usize, format: OffsetFormat) {
+    for format in [format, format.other()] {
+        let text = format.format(offset);
+        if ui.button(format!("Copy {}", text)).clicked() {
+            ui.ctx().copy_text(text);
+        }
+    }
+}
+
+/// Signed distance from `anchor` to `offset`, in hex and decimal, e.g. `+0x20 (+32)`.
+pub fn format_delta(anchor: usize, offset: usize) -> String {
+    let delta = offset as i128 - anchor as i128;
+    let sign = if delta < 0 { '-' } else { '+' };
+    format!("{}0x{:X} ({:+})", sign, delta.unsigned_abs(), delta)
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Selection {
     start: usize,
@@ -76,12 +292,21 @@ impl Selection {
         Self { start, end }
     }
 
+    /// Every byte of `len` bytes of data, or `None` when there are none.
+    pub fn whole(len: usize) -> Option<Self> {
+        len.checked_sub(1).map(|end| Self::range(0, end))
+    }
+
     pub fn lower(&self) -> usize {
-        return usize::min(self.start, self.end);
+        usize::min(self.start, self.end)
     }
 
     pub fn upper(&self) -> usize {
-        return usize::max(self.start, self.end);
+        usize::max(self.start, self.end)
+    }
+
+    pub fn byte_count(&self) -> usize {
+        self.upper() - self.lower() + 1
     }
 
     pub fn contains(&self, offset: usize) -> bool {
@@ -97,8 +322,187 @@ impl Selection {
     }
 }
 
+/// Browser-style Back/Forward over the places jumped to.
+#[derive(Debug, Default)]
+pub struct NavHistory {
+    back: Vec<Selection>,
+    forward: Vec<Selection>,
+}
+
+impl NavHistory {
+    const MAX_ENTRIES: usize = 100;
+
+    /// Records leaving `from` for somewhere new, which drops the forward entries.
+    pub fn visit(&mut self, from: Option<Selection>) {
+        let Some(from) = from else {
+            return;
+        };
+        if self.back.last() != Some(&from) {
+            self.back.push(from);
+            if self.back.len() > Self::MAX_ENTRIES {
+                self.back.remove(0);
+            }
+        }
+        self.forward.clear();
+    }
+
+    pub fn back(&mut self, current: Option<Selection>) -> Option<Selection> {
+        let to = self.back.pop()?;
+        self.forward.extend(current);
+        Some(to)
+    }
+
+    pub fn forward(&mut self, current: Option<Selection>) -> Option<Selection> {
+        let to = self.forward.pop()?;
+        self.back.extend(current);
+        Some(to)
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        !self.back.is_empty()
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        !self.forward.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.back.clear();
+        self.forward.clear();
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Bookmark {
+    pub name: String,
+    pub offset: usize,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct SearchResult {
     pub index: usize,
     pub offset: usize,
+    /// Bytes covered by the match.
+    pub len: usize,
+    /// Which of the values searched for matched.
+    pub pattern: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offset_format_origin() {
+        let whole: OffsetFormat = OffsetBase::Hexadecimal.into();
+        assert_eq!(whole.format(0x1F), "0x0000001F");
+        let window = OffsetFormat {
+            base: OffsetBase::Hexadecimal,
+            origin: 0x1000_0000,
+        };
+        assert_eq!(window.format(0x1F), "0x1000001F");
+        assert_eq!(window.format_column(0x10), "10000010");
+        assert_eq!(window.other().format(1), "268435457");
+    }
+
+    #[test]
+    fn test_format_delta() {
+        assert_eq!(format_delta(0x10, 0x30), "+0x20 (+32)");
+        assert_eq!(format_delta(0x30, 0x10), "-0x20 (-32)");
+        assert_eq!(format_delta(5, 5), "+0x0 (+0)");
+    }
+
+    #[test]
+    fn test_encode_single_byte() {
+        assert_eq!(Encoding::Latin1.encode("café"), Ok(b"caf\xE9".to_vec()));
+        assert_eq!(Encoding::Latin1.encode("€"), Err('€'));
+        assert_eq!(
+            Encoding::Windows1252.encode("café €5"),
+            Ok(b"caf\xE9 \x805".to_vec())
+        );
+        assert_eq!(
+            Encoding::Windows1252.encode("“ok”"),
+            Ok(b"\x93ok\x94".to_vec())
+        );
+        // C1 controls are Latin-1 only; Windows-1252 uses those bytes for other characters
+        assert_eq!(Encoding::Latin1.encode("\u{80}"), Ok(vec![0x80]));
+        assert_eq!(Encoding::Windows1252.encode("\u{80}"), Err('\u{80}'));
+    }
+
+    #[test]
+    fn test_encode_cjk() {
+        assert_eq!(Encoding::ShiftJis.encode("OK"), Ok(b"OK".to_vec()));
+        assert_eq!(Encoding::EucJp.encode("OK"), Ok(b"OK".to_vec()));
+        #[cfg(feature = "cjk")]
+        {
+            assert_eq!(
+                Encoding::ShiftJis.encode("日本ｱ"),
+                Ok(vec![0x93, 0xFA, 0x96, 0x7B, 0xB1])
+            );
+            assert_eq!(
+                Encoding::EucJp.encode("日本ｱ"),
+                Ok(vec![0xC6, 0xFC, 0xCB, 0xDC, 0x8E, 0xB1])
+            );
+            assert_eq!(Encoding::ShiftJis.encode("a😀"), Err('😀'));
+        }
+        #[cfg(not(feature = "cjk"))]
+        assert_eq!(Encoding::ShiftJis.encode("a日本"), Err('日'));
+    }
+
+    #[test]
+    fn test_encode_utf16() {
+        assert_eq!(Encoding::Utf16Le.encode("Hi"), Ok(vec![b'H', 0, b'i', 0]));
+        assert_eq!(Encoding::Utf16Be.encode("Hi"), Ok(vec![0, b'H', 0, b'i']));
+        assert_eq!(
+            Encoding::Utf16Le.encode("😀"),
+            Ok(vec![0x3D, 0xD8, 0x00, 0xDE])
+        );
+
+        let text = "a😀é";
+        let bytes = Encoding::Utf16Be.encode(text).unwrap();
+        let units = bytes
+            .chunks_exact(2)
+            .map(|c| u16::from_be_bytes([c[0], c[1]]));
+        let decoded: Result<String, _> = char::decode_utf16(units).collect();
+        assert_eq!(decoded.as_deref(), Ok(text));
+    }
+
+    #[test]
+    fn test_nav_history() {
+        let at = |offset| Some(Selection::new(offset));
+        let mut history = NavHistory::default();
+        assert_eq!(history.back(at(0)), None);
+
+        history.visit(at(0));
+        history.visit(at(10));
+        assert_eq!(history.back(at(20)), at(10));
+        assert_eq!(history.back(at(10)), at(0));
+        assert!(!history.can_go_back());
+        assert_eq!(history.forward(at(0)), at(10));
+        assert_eq!(history.forward(at(10)), at(20));
+        assert!(!history.can_go_forward());
+
+        // A new jump after going back discards what was ahead
+        history.back(at(20));
+        history.visit(at(10));
+        assert!(!history.can_go_forward());
+    }
+
+    #[test]
+    fn test_detect_bom() {
+        assert_eq!(
+            Encoding::detect_bom(b"\xEF\xBB\xBFhi"),
+            Some(Encoding::UTF8)
+        );
+        assert_eq!(
+            Encoding::detect_bom(b"\xFF\xFEh\0"),
+            Some(Encoding::Utf16Le)
+        );
+        assert_eq!(
+            Encoding::detect_bom(b"\xFE\xFF\0h"),
+            Some(Encoding::Utf16Be)
+        );
+        assert_eq!(Encoding::detect_bom(b"\xEF\xBB"), None);
+        assert_eq!(Encoding::detect_bom(b""), None);
+    }
 }